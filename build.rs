@@ -1,6 +1,11 @@
+#[cfg(feature = "gui")]
 use std::iter;
 
 fn main() {
-    slint_build::compile("ui/main.slint").unwrap();
-	let _ = embed_resource::compile("app.rc", iter::empty::<&str>());
+    // Only the window needs the compiled UI and the Windows resources
+    #[cfg(feature = "gui")]
+    {
+        slint_build::compile("ui/main.slint").unwrap();
+        let _ = embed_resource::compile("app.rc", iter::empty::<&str>());
+    }
 }
@@ -0,0 +1,15 @@
+//! Console build of the TwinCAN command line.
+//!
+//! Release builds of TwinCAN use the Windows GUI subsystem, so on Windows
+//! they print nothing to the console that started them and the shell does
+//! not wait for their exit code. This executable runs the same `diff`,
+//! `diff3` and `lint` commands as a console program, for scripts and CI.
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let code = twincan::cli::run(&args).unwrap_or_else(|| {
+        eprintln!("{}", twincan::cli::USAGE);
+        2
+    });
+    std::process::exit(code);
+}
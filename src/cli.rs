@@ -0,0 +1,308 @@
+//! The `diff`, `diff3` and `lint` commands, run without opening a window.
+//!
+//! Both the TwinCAN application and the console-only `twincan-cli`
+//! executable run them through [`run`].
+
+use crate::compare::{compare_dbc_files_with, CompareOptions};
+use crate::ignore::load_ignore_rules;
+use crate::lint::lint_dbc_file;
+use crate::load_dbc;
+use crate::model::ComparisonResult;
+use crate::project::Project;
+use crate::report::{render, render_three_way};
+use crate::severity::{classify, filter_by_severity, Severity};
+use crate::similarity::parse_threshold;
+use crate::three_way::{compare_three_way, ThreeWayChange};
+
+/// Usage of the commands, printed when none is given.
+pub const USAGE: &str = "Usage: twincan-cli diff|diff3|lint ...";
+
+/// Runs the command named by the first argument and returns the process
+/// exit code, or `None` when the arguments do not name a command.
+pub fn run(args: &[String]) -> Option<i32> {
+    match args.first().map(String::as_str) {
+        Some("diff") => Some(run_diff_command(&args[1..])),
+        Some("diff3") => Some(run_diff3_command(&args[1..])),
+        Some("lint") => Some(run_lint_command(&args[1..])),
+        _ => None,
+    }
+}
+
+/// Options and paths given to `twincan-cli diff` or `twincan-cli diff3`.
+struct DiffArgs<'a> {
+    options: CompareOptions,
+    min_severity: Severity,
+    paths: Vec<&'a str>,
+    project: Option<Project>,
+}
+
+/// Parses `[--project <file>] [--match <mode>] [--min-severity <level>]
+/// [--tolerance <settings>]... [--text-match <strategy>] [--text-threshold <similarity>]
+/// [--ignore-file <rules>]` followed by paths.
+///
+/// A project file provides the options; the other flags override them.
+fn parse_diff_args(args: &[String]) -> Result<DiffArgs<'_>, String> {
+    // Load the project first, so flags override it wherever they are given
+    let project = match args.iter().position(|arg| arg == "--project").map(|index| args.get(index + 1)) {
+        Some(Some(path)) => Some(Project::load(path).map_err(|e| format!("Error loading project: {}", e))?),
+        Some(None) => return Err("--project expects the path of a .twincan project file".to_string()),
+        None => None,
+    };
+
+    let mut options = project.as_ref().map(Project::compare_options).unwrap_or_default();
+    let mut min_severity = Severity::Cosmetic;
+    let mut tolerances = Vec::new();
+    let mut paths = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--project" => {
+                args.next();
+            }
+            "--match" => match args.next().map(|mode| mode.parse()) {
+                Some(mode) => options.match_mode = mode?,
+                None => return Err("--match expects one of: id, name, id-then-name".to_string()),
+            },
+            "--min-severity" => match args.next().map(|severity| severity.parse()) {
+                Some(severity) => min_severity = severity?,
+                None => {
+                    return Err("--min-severity expects one of: cosmetic, potentially-breaking, breaking".to_string())
+                }
+            },
+            "--text-match" => match args.next().map(|strategy| strategy.parse()) {
+                Some(strategy) => options.text_matching.strategy = strategy?,
+                None => {
+                    return Err(
+                        "--text-match expects one of: exact, case-insensitive, normalized, jaro-winkler, levenshtein"
+                            .to_string(),
+                    )
+                }
+            },
+            "--text-threshold" => match args.next().map(|threshold| parse_threshold(threshold)) {
+                Some(threshold) => options.text_matching.threshold = threshold?,
+                None => return Err("--text-threshold expects a similarity between 0 and 1".to_string()),
+            },
+            "--ignore-file" => match args.next().map(|path| load_ignore_rules(path)) {
+                Some(Ok(rules)) => options.ignore_rules = rules,
+                Some(Err(e)) => return Err(format!("Error loading ignore rules: {}", e)),
+                None => return Err("--ignore-file expects the path of a TOML file with [[ignore]] rules".to_string()),
+            },
+            "--tolerance" => match args.next() {
+                Some(settings) => tolerances.push(settings.as_str()),
+                None => return Err("--tolerance expects field=value, such as factor=1e-6 or all=0.01%".to_string()),
+            },
+            _ => paths.push(arg.as_str()),
+        }
+    }
+
+    // Later settings override earlier ones, across flags as within one
+    if !tolerances.is_empty() {
+        options.tolerances = tolerances.join(",").parse()?;
+    }
+
+    Ok(DiffArgs { options, min_severity, paths, project })
+}
+
+/// Options accepted by both `twincan-cli diff` and `twincan-cli diff3`, for usage messages.
+const DIFF_OPTIONS_USAGE: &str = "[--project file.twincan] [--match id|name|id-then-name] \
+     [--min-severity cosmetic|potentially-breaking|breaking] [--tolerance field=value]... \
+     [--text-match exact|case-insensitive|normalized|jaro-winkler|levenshtein] \
+     [--text-threshold 0..1] [--ignore-file rules.toml]";
+
+/// Runs `twincan-cli diff [options] <dbc1> <dbc2>` without opening a window;
+/// see [`parse_diff_args`] for the options.
+///
+/// When a project file is given without paths, the file pair comes from the
+/// project.
+///
+/// Prints one tab-separated line per difference at or above the minimum
+/// severity to stdout and returns the process exit code: 0 when no such
+/// differences were found, 1 when some were and 2 when the arguments or
+/// files could not be used.
+fn run_diff_command(args: &[String]) -> i32 {
+    let DiffArgs { options, min_severity, paths, project } = match parse_diff_args(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 2;
+        }
+    };
+
+    let (dbc1_path, dbc2_path) = match (&paths[..], &project) {
+        ([dbc1, dbc2], _) => (*dbc1, *dbc2),
+        ([], Some(project)) if !project.dbc1.is_empty() && !project.dbc2.is_empty() => {
+            (project.dbc1.as_str(), project.dbc2.as_str())
+        }
+        _ => {
+            eprintln!("Usage: twincan-cli diff {} <dbc1> <dbc2>", DIFF_OPTIONS_USAGE);
+            return 2;
+        }
+    };
+
+    let dbc1 = match load_dbc(dbc1_path) {
+        Ok(dbc) => dbc,
+        Err(e) => {
+            eprintln!("Error loading DBC1: {}", e);
+            return 2;
+        }
+    };
+    let dbc2 = match load_dbc(dbc2_path) {
+        Ok(dbc) => dbc,
+        Err(e) => {
+            eprintln!("Error loading DBC2: {}", e);
+            return 2;
+        }
+    };
+
+    let results = match compare_dbc_files_with(&dbc1, &dbc2, &options) {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("Error during comparison: {}", e);
+            return 2;
+        }
+    };
+
+    let results = filter_by_severity(&results, min_severity, false);
+    print_results(&results, ("DBC1", "DBC2"));
+    eprintln!("Comparison complete. Found {} differences.", results.len());
+
+    if results.is_empty() { 0 } else { 1 }
+}
+
+/// Runs `twincan-cli diff3 [options] <base> <a> <b>` without opening a window,
+/// comparing versions A and B against their common ancestor; see
+/// [`parse_diff_args`] for the options.
+///
+/// A project file only supplies options; the three files are always given
+/// as paths, since a project holds a pair of files rather than three.
+///
+/// Prints one tab-separated line per change at or above the minimum
+/// severity to stdout and returns the process exit code: 0 when no such
+/// changes conflict, 1 when some do and 2 when the arguments or files could
+/// not be used.
+fn run_diff3_command(args: &[String]) -> i32 {
+    let DiffArgs { options, min_severity, paths, .. } = match parse_diff_args(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 2;
+        }
+    };
+
+    let [base_path, a_path, b_path] = paths[..] else {
+        eprintln!("Usage: twincan-cli diff3 {} <base> <a> <b>", DIFF_OPTIONS_USAGE);
+        return 2;
+    };
+
+    let mut files = Vec::new();
+    for (name, path) in [("base", base_path), ("A", a_path), ("B", b_path)] {
+        match load_dbc(path) {
+            Ok(dbc) => files.push(dbc),
+            Err(e) => {
+                eprintln!("Error loading {}: {}", name, e);
+                return 2;
+            }
+        }
+    }
+
+    let results = match compare_three_way(&files[0], &files[1], &files[2], &options) {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("Error during comparison: {}", e);
+            return 2;
+        }
+    };
+
+    println!("Change\tType\tSeverity\tMessage\tSignal\tField\tBase\tA\tB\tDetails");
+    let mut conflicts = 0;
+    let mut shown = 0;
+    for result in &results {
+        if result.a.iter().chain(&result.b).map(classify).max() < Some(min_severity) {
+            continue;
+        }
+        let row = render_three_way(result);
+        println!(
+            "{}",
+            tsv_line(&[
+                &row.change, &row.result_type, &row.severity, &row.message, &row.signal, &row.field, &row.base,
+                &row.a, &row.b, &row.details,
+            ])
+        );
+        shown += 1;
+        if result.change == ThreeWayChange::Conflict {
+            conflicts += 1;
+        }
+    }
+    eprintln!("Three-way comparison complete. Found {} changes, {} conflicting.", shown, conflicts);
+
+    if conflicts == 0 { 0 } else { 1 }
+}
+
+/// Runs `twincan-cli lint <dbc>` without opening a window.
+///
+/// Prints one tab-separated line per problem to stdout and returns the
+/// process exit code: 0 when no problems were found, 1 when some were and
+/// 2 when the arguments or file could not be used.
+fn run_lint_command(args: &[String]) -> i32 {
+    let path = match args {
+        [path] => path,
+        _ => {
+            eprintln!("Usage: twincan-cli lint <dbc>");
+            return 2;
+        }
+    };
+
+    let dbc = match load_dbc(path) {
+        Ok(dbc) => dbc,
+        Err(e) => {
+            eprintln!("Error loading DBC: {}", e);
+            return 2;
+        }
+    };
+
+    let results = lint_dbc_file(&dbc);
+    print_results(&results, ("Value", "Reference"));
+    eprintln!("Lint complete. Found {} problems.", results.len());
+
+    if results.is_empty() { 0 } else { 1 }
+}
+
+/// Prints results as tab-separated lines with a header line naming the two
+/// value columns.
+fn print_results(results: &[ComparisonResult], (value1, value2): (&str, &str)) {
+    println!("Type\tSeverity\tMessage\tSignal\tField\t{}\t{}\tDetails", value1, value2);
+    for result in results {
+        let row = render(result);
+        println!(
+            "{}",
+            tsv_line(&[
+                &row.result_type, &row.severity, &row.message, &row.signal, &row.field, &row.dbc1, &row.dbc2,
+                &row.details,
+            ])
+        );
+    }
+}
+
+/// Joins fields into one tab-separated line. Backslashes, tabs and line
+/// breaks within a field, as comments can hold, are written as `\\`, `\t`,
+/// `\r` and `\n` so each row stays on one line with the same columns.
+fn tsv_line(fields: &[&str]) -> String {
+    let escaped: Vec<String> = fields
+        .iter()
+        .map(|field| field.replace('\\', "\\\\").replace('\t', "\\t").replace('\r', "\\r").replace('\n', "\\n"))
+        .collect();
+    escaped.join("\t")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tsv_fields_stay_on_one_line() {
+        let line = tsv_line(&["Signal", "Comment", "Two\tcolumns\r\nand lines", "C:\\dbc"]);
+        assert_eq!(line, "Signal\tComment\tTwo\\tcolumns\\r\\nand lines\tC:\\\\dbc");
+        assert_eq!(line.split('\t').count(), 4);
+    }
+}
@@ -0,0 +1,1543 @@
+//! Comparison of two parsed DBC files into a list of differences.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::hash::Hash;
+use std::str::FromStr;
+use rs_dbc::{Dbc, Message, MessageID};
+use serde::{Deserialize, Serialize};
+use crate::network::{
+    AttributeDefinition, AttributeType, AttributeValue, DbcFile, ExtendedValueType, Network, ObjectRef,
+};
+use crate::model::{
+    ChangeKind, ComparisonResult, Detail, EntityKind, Field, IdFormat, MultiplexerType, RangeFit, Value, ValueType,
+};
+use crate::ignore::{apply_ignore_rules, default_ignore_rules, IgnoreRule};
+use crate::layout::{bit_runs, signal_bits};
+use crate::similarity::{calculate_similarity, TextMatching};
+use crate::tolerance::{Tolerance, Tolerances};
+
+/// How messages of the two files are paired before they are compared.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MatchMode {
+    /// Pair messages with the same name.
+    Name,
+    /// Pair messages with the same CAN ID.
+    Id,
+    /// Pair messages with the same CAN ID, then pair the rest by name.
+    #[default]
+    IdThenName,
+}
+
+impl FromStr for MatchMode {
+    type Err = String;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "name" => Ok(MatchMode::Name),
+            "id" => Ok(MatchMode::Id),
+            "id-then-name" => Ok(MatchMode::IdThenName),
+            _ => Err(format!("Unknown match mode '{}', expected id, name or id-then-name", mode)),
+        }
+    }
+}
+
+/// Options controlling how two DBC files are compared.
+#[derive(Clone, Debug)]
+pub struct CompareOptions {
+    /// How messages are paired between the two files.
+    pub match_mode: MatchMode,
+    /// How far apart floating-point fields may be and still count as equal.
+    pub tolerances: Tolerances,
+    /// How value descriptions and comments are matched.
+    pub text_matching: TextMatching,
+    /// Differences to leave out of the results.
+    pub ignore_rules: Vec<IgnoreRule>,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        CompareOptions {
+            match_mode: MatchMode::default(),
+            tolerances: Tolerances::default(),
+            text_matching: TextMatching::default(),
+            ignore_rules: default_ignore_rules(),
+        }
+    }
+}
+
+/// Compares two parsed DBC files with the default [`CompareOptions`].
+pub fn compare_dbc_files(dbc1: &DbcFile, dbc2: &DbcFile) -> Result<Vec<ComparisonResult>, Box<dyn Error>> {
+    compare_dbc_files_with(dbc1, dbc2, &CompareOptions::default())
+}
+
+/// Compares two parsed DBC files.
+///
+/// Nodes, attribute definitions, network attributes, network comments, value
+/// tables and environment variables are compared first, then messages.
+/// Messages are paired according to `options.match_mode` and their signals
+/// by signal name. A message paired under a different name is reported as a
+/// rename and its properties and signals are compared as usual. The returned
+/// differences are sorted by message name, then signal name, and are the
+/// single source for the results table and every exporter.
+pub fn compare_dbc_files_with(
+    dbc1: &DbcFile,
+    dbc2: &DbcFile,
+    options: &CompareOptions,
+) -> Result<Vec<ComparisonResult>, Box<dyn Error>> {
+
+    let mut results = Vec::new();
+
+    compare_nodes_for_results(&mut results, &dbc1.network, &dbc2.network, options);
+    compare_attribute_definitions_for_results(&mut results, &dbc1.network, &dbc2.network);
+    compare_attribute_values_for_results(
+        &mut results,
+        (&dbc1.network, &ObjectRef::Network),
+        (&dbc2.network, &ObjectRef::Network),
+        ComparisonResult::for_network,
+    );
+    compare_comments_for_results(
+        &mut results,
+        (&dbc1.network, &ObjectRef::Network),
+        (&dbc2.network, &ObjectRef::Network),
+        &options.text_matching,
+        ComparisonResult::for_network,
+    );
+    compare_value_tables_for_results(&mut results, dbc1, dbc2, options);
+    compare_environment_variables_for_results(&mut results, &dbc1.network, &dbc2.network, options);
+
+    for (msg1, msg2) in pair_messages(&dbc1.dbc, &dbc2.dbc, options.match_mode) {
+        match (msg1, msg2) {
+            (Some(m1), Some(m2)) => {
+                // Both DBCs have this message - compare properties
+                let pair = PairedMessages { dbc1, dbc2, msg1: m1, msg2: m2, options };
+                compare_message_properties_for_results(&mut results, &pair);
+                compare_signals_for_results(&mut results, &pair);
+            },
+            (Some(m1), None) => {
+                // Only DBC1 has this message
+                results.push(ComparisonResult::exists(EntityKind::Message, m1.message_name(), None, ChangeKind::Removed));
+            },
+            (None, Some(m2)) => {
+                // Only DBC2 has this message
+                results.push(ComparisonResult::exists(EntityKind::Message, m2.message_name(), None, ChangeKind::Added));
+            },
+            (None, None) => unreachable!(),
+        }
+    }
+
+    apply_ignore_rules(&mut results, &options.ignore_rules);
+    note_tolerances(&mut results, &options.tolerances);
+    Ok(results)
+}
+
+/// Finds the messages that were compared under `message_name`.
+///
+/// Results name a message by its name in DBC1, or in DBC2 for messages only
+/// it has; this looks up the pair such a name refers to, paired the same way
+/// as [`compare_dbc_files_with`] pairs them for `match_mode`.
+pub fn find_message_pair<'a>(
+    dbc1: &'a DbcFile,
+    dbc2: &'a DbcFile,
+    message_name: &str,
+    match_mode: MatchMode,
+) -> (Option<&'a Message>, Option<&'a Message>) {
+    pair_messages(&dbc1.dbc, &dbc2.dbc, match_mode)
+        .into_iter()
+        .find(|pair| pair_name(pair) == message_name)
+        .unwrap_or((None, None))
+}
+
+/// Minimum confidence for a removed and an added signal to be reported as a rename.
+const RENAME_CONFIDENCE_THRESHOLD: f64 = 0.8;
+
+/// Attributes that are already compared as dedicated message or signal fields.
+const DEDICATED_ATTRIBUTES: [&str; 2] = ["GenMsgCycleTime", "GenSigStartValue"];
+
+/// Two paired messages, the files they come from and the options they are
+/// compared with.
+struct PairedMessages<'a> {
+    dbc1: &'a DbcFile,
+    dbc2: &'a DbcFile,
+    msg1: &'a Message,
+    msg2: &'a Message,
+    options: &'a CompareOptions,
+}
+
+fn compare_nodes_for_results(
+    results: &mut Vec<ComparisonResult>,
+    network1: &Network,
+    network2: &Network,
+    options: &CompareOptions,
+) {
+    let nodes1 = &network1.nodes;
+    let nodes2 = &network2.nodes;
+
+    // Sort node names alphabetically
+    let mut all_nodes: Vec<&String> = nodes1.iter().chain(nodes2).collect();
+    all_nodes.sort();
+    all_nodes.dedup();
+
+    for node in all_nodes {
+        match (nodes1.contains(node), nodes2.contains(node)) {
+            (true, true) => {
+                // Both DBCs have this node - compare its attributes and comment
+                let target = ObjectRef::Node(node.clone());
+                let make_result =
+                    |field, value1, value2| ComparisonResult::for_object(EntityKind::Node, node, field, value1, value2);
+                compare_attribute_values_for_results(results, (network1, &target), (network2, &target), make_result);
+                let matching = &options.text_matching;
+                compare_comments_for_results(results, (network1, &target), (network2, &target), matching, make_result);
+            },
+            (true, false) => results.push(ComparisonResult::object_exists(EntityKind::Node, node, ChangeKind::Removed)),
+            (false, true) => results.push(ComparisonResult::object_exists(EntityKind::Node, node, ChangeKind::Added)),
+            (false, false) => unreachable!(),
+        }
+    }
+}
+
+fn compare_attribute_definitions_for_results(results: &mut Vec<ComparisonResult>, network1: &Network, network2: &Network) {
+    let definitions1 = &network1.attribute_definitions;
+    let definitions2 = &network2.attribute_definitions;
+
+    for (name, def1, def2) in pair_by_key(definitions1, definitions2) {
+        let (def1, def2) = match (def1, def2) {
+            (Some(def1), Some(def2)) => (def1, def2),
+            (Some(_), None) => {
+                results.push(ComparisonResult::object_exists(EntityKind::Attribute, name, ChangeKind::Removed));
+                continue;
+            },
+            (None, _) => {
+                results.push(ComparisonResult::object_exists(EntityKind::Attribute, name, ChangeKind::Added));
+                continue;
+            },
+        };
+        let mut push = |field: Field, dbc1: Option<Value>, dbc2: Option<Value>| {
+            results.push(ComparisonResult::for_object(EntityKind::Attribute, name, field, dbc1, dbc2));
+        };
+
+        // Compare the kind of object the attribute applies to
+        if def1.object != def2.object {
+            push(
+                Field::AttributeObject,
+                Some(Value::AttributeObject(def1.object)),
+                Some(Value::AttributeObject(def2.object)),
+            );
+        }
+
+        // Compare value type, range and enum values
+        let (kind1, range1, enum1) = describe_attribute_type(&def1.value_type);
+        let (kind2, range2, enum2) = describe_attribute_type(&def2.value_type);
+        if kind1 != kind2 {
+            push(Field::AttributeType, Some(Value::Text(kind1.to_string())), Some(Value::Text(kind2.to_string())));
+        }
+        if range1 != range2 {
+            push(Field::AttributeRange, range1, range2);
+        }
+        if enum1 != enum2 {
+            push(Field::EnumValues, enum1, enum2);
+        }
+
+        // Compare default value
+        let default1 = network1.attribute_defaults.get(name).map(|value| attribute_value(network1, name, value));
+        let default2 = network2.attribute_defaults.get(name).map(|value| attribute_value(network2, name, value));
+        if default1 != default2 {
+            push(Field::AttributeDefault, default1, default2);
+        }
+    }
+}
+
+fn compare_value_tables_for_results(
+    results: &mut Vec<ComparisonResult>,
+    dbc1: &DbcFile,
+    dbc2: &DbcFile,
+    options: &CompareOptions,
+) {
+    let tables1 = &dbc1.network.value_tables;
+    let tables2 = &dbc2.network.value_tables;
+    let references1 = value_table_references(dbc1);
+    let references2 = value_table_references(dbc2);
+
+    for (name, table1, table2) in pair_by_key(tables1, tables2) {
+        let users1 = references1.get(name.as_str()).cloned().unwrap_or_default();
+        let users2 = references2.get(name.as_str()).cloned().unwrap_or_default();
+
+        // Name the signals using the table in either file on every change
+        let mut users: Vec<String> = users1.iter().chain(&users2).cloned().collect();
+        users.sort();
+        users.dedup();
+        let with_users = |result: ComparisonResult| {
+            if users.is_empty() { result } else { result.with_detail(Detail::ReferencedBy(users.clone())) }
+        };
+
+        let (table1, table2) = match (table1, table2) {
+            (Some(table1), Some(table2)) => (table1, table2),
+            (Some(_), None) => {
+                results.push(with_users(ComparisonResult::object_exists(EntityKind::ValueTable, name, ChangeKind::Removed)));
+                continue;
+            },
+            (None, _) => {
+                results.push(with_users(ComparisonResult::object_exists(EntityKind::ValueTable, name, ChangeKind::Added)));
+                continue;
+            },
+        };
+
+        // Compare entries, in order of raw value
+        let make_result = |field, value1, value2| {
+            with_users(ComparisonResult::for_object(EntityKind::ValueTable, name, field, value1, value2))
+        };
+        for (value, desc1, desc2) in pair_by_key(table1, table2) {
+            let desc1 = desc1.and_then(|d| text_value(d));
+            let desc2 = desc2.and_then(|d| text_value(d));
+            compare_texts_for_results(
+                results,
+                Field::ValueDescription(*value),
+                (desc1, desc2),
+                &options.text_matching,
+                make_result,
+            );
+        }
+
+        // Compare the signals using the table
+        if users1 != users2 {
+            results.push(ComparisonResult::for_object(
+                EntityKind::ValueTable,
+                name,
+                Field::ReferencedBy,
+                list_value(users1),
+                list_value(users2),
+            ));
+        }
+    }
+}
+
+fn compare_environment_variables_for_results(
+    results: &mut Vec<ComparisonResult>,
+    network1: &Network,
+    network2: &Network,
+    options: &CompareOptions,
+) {
+    let tolerances = &options.tolerances;
+    let variables1 = &network1.environment_variables;
+    let variables2 = &network2.environment_variables;
+
+    for (name, var1, var2) in pair_by_key(variables1, variables2) {
+        let (var1, var2) = match (var1, var2) {
+            (Some(var1), Some(var2)) => (var1, var2),
+            (Some(_), None) => {
+                results.push(ComparisonResult::object_exists(EntityKind::EnvironmentVariable, name, ChangeKind::Removed));
+                continue;
+            },
+            (None, _) => {
+                results.push(ComparisonResult::object_exists(EntityKind::EnvironmentVariable, name, ChangeKind::Added));
+                continue;
+            },
+        };
+        let make_result = |field, value1, value2| {
+            ComparisonResult::for_object(EntityKind::EnvironmentVariable, name, field, value1, value2)
+        };
+        let mut push = |field: Field, dbc1: Option<Value>, dbc2: Option<Value>| {
+            results.push(make_result(field, dbc1, dbc2));
+        };
+
+        // Compare value type
+        if var1.variable_type != var2.variable_type {
+            push(
+                Field::VariableType,
+                Some(Value::VariableType(var1.variable_type)),
+                Some(Value::VariableType(var2.variable_type)),
+            );
+        }
+
+        // Compare min and max value
+        if tolerances.min.differs(var1.min, var2.min) {
+            push(Field::MinValue, Some(Value::Float(var1.min)), Some(Value::Float(var2.min)));
+        }
+        if tolerances.max.differs(var1.max, var2.max) {
+            push(Field::MaxValue, Some(Value::Float(var1.max)), Some(Value::Float(var2.max)));
+        }
+
+        // Compare unit
+        let unit1 = text_value(&var1.unit);
+        let unit2 = text_value(&var2.unit);
+        if unit1 != unit2 {
+            push(Field::Unit, unit1, unit2);
+        }
+
+        // Compare initial value
+        if tolerances.initial.differs(var1.initial_value, var2.initial_value) {
+            push(Field::InitialValue, Some(Value::Float(var1.initial_value)), Some(Value::Float(var2.initial_value)));
+        }
+
+        // Compare access type and access nodes
+        if var1.access_type != var2.access_type {
+            push(Field::AccessType, Some(Value::AccessType(var1.access_type)), Some(Value::AccessType(var2.access_type)));
+        }
+        if var1.access_nodes != var2.access_nodes {
+            push(Field::AccessNodes, receivers_value(&var1.access_nodes), receivers_value(&var2.access_nodes));
+        }
+
+        // Compare data length
+        if var1.data_size != var2.data_size {
+            push(Field::DataSize, var1.data_size.map(Value::Integer), var2.data_size.map(Value::Integer));
+        }
+
+        // Compare attributes and comment
+        let target = ObjectRef::EnvironmentVariable(name.clone());
+        compare_attribute_values_for_results(results, (network1, &target), (network2, &target), make_result);
+        let matching = &options.text_matching;
+        compare_comments_for_results(results, (network1, &target), (network2, &target), matching, make_result);
+    }
+}
+
+/// Finds the signals, as `Message.Signal`, that use each value table.
+///
+/// DBC files do not record which table a signal's `VAL_` entries came from,
+/// so a signal is taken to use a table with exactly the same entries. When
+/// several tables have those entries, the table whose name is most similar
+/// to the signal name is used.
+fn value_table_references(file: &DbcFile) -> HashMap<&str, Vec<String>> {
+    let mut references: HashMap<&str, Vec<String>> = HashMap::new();
+
+    for msg in &file.dbc.messages {
+        for signal in &msg.signals {
+            let descriptions = file.network.value_descriptions_of(&signal_ref(msg, signal));
+            if descriptions.is_empty() {
+                continue;
+            }
+
+            let table = file
+                .network
+                .value_tables
+                .iter()
+                .filter(|(_, entries)| **entries == descriptions)
+                .max_by(|(a, _), (b, _)| {
+                    calculate_similarity(a, signal.name()).total_cmp(&calculate_similarity(b, signal.name()))
+                });
+            if let Some((name, _)) = table {
+                references.entry(name).or_default().push(format!("{}.{}", msg.message_name(), signal.name()));
+            }
+        }
+    }
+
+    for signals in references.values_mut() {
+        signals.sort();
+    }
+    references
+}
+
+/// Splits an attribute type into its keyword, numeric range and enum values.
+fn describe_attribute_type(value_type: &AttributeType) -> (&'static str, Option<Value>, Option<Value>) {
+    match value_type {
+        AttributeType::Int { min, max } => ("INT", Some(Value::Range(*min, *max)), None),
+        AttributeType::Hex { min, max } => ("HEX", Some(Value::Range(*min, *max)), None),
+        AttributeType::Float { min, max } => ("FLOAT", Some(Value::Range(*min, *max)), None),
+        AttributeType::String => ("STRING", None, None),
+        AttributeType::Enum(values) => ("ENUM", None, Some(Value::List(values.clone()))),
+    }
+}
+
+/// Compares the attribute values set on one object in each file.
+///
+/// An attribute not set on the object takes the file's default. Attributes
+/// left at their default on both sides are not reported here; a changed
+/// default is reported once with the attribute definition instead.
+fn compare_attribute_values_for_results(
+    results: &mut Vec<ComparisonResult>,
+    (network1, target1): (&Network, &ObjectRef),
+    (network2, target2): (&Network, &ObjectRef),
+    make_result: impl Fn(Field, Option<Value>, Option<Value>) -> ComparisonResult,
+) {
+    let values1 = network1.attributes_of(target1);
+    let values2 = network2.attributes_of(target2);
+
+    for (name, value1, value2) in pair_by_key(&values1, &values2) {
+        if DEDICATED_ATTRIBUTES.contains(&name.as_str()) {
+            continue;
+        }
+
+        let value1 = value1
+            .or_else(|| network1.attribute_defaults.get(name))
+            .map(|value| attribute_value(network1, name, value));
+        let value2 = value2
+            .or_else(|| network2.attribute_defaults.get(name))
+            .map(|value| attribute_value(network2, name, value));
+
+        if value1 != value2 {
+            results.push(make_result(Field::Attribute(name.clone()), value1, value2));
+        }
+    }
+}
+
+/// Converts an attribute value, resolving enum indices to their labels.
+fn attribute_value(network: &Network, name: &str, value: &AttributeValue) -> Value {
+    let labels = match network.attribute_definitions.get(name) {
+        Some(AttributeDefinition { value_type: AttributeType::Enum(labels), .. }) => Some(labels),
+        _ => None,
+    };
+
+    match (value, labels) {
+        (AttributeValue::Number(index), Some(labels)) if index.fract() == 0.0 && *index >= 0.0 => {
+            match labels.get(*index as usize) {
+                Some(label) => Value::Text(label.clone()),
+                None => Value::Float(*index),
+            }
+        },
+        (AttributeValue::Number(number), _) => Value::Float(*number),
+        (AttributeValue::Text(text), _) => Value::Text(text.clone()),
+    }
+}
+
+/// Message ID as written in the file, without the extended-frame flag rs_dbc adds.
+pub(crate) fn file_message_id(msg: &Message) -> u32 {
+    match msg.message_id {
+        MessageID::Standard(id) => id.into(),
+        MessageID::Extended(id) => id,
+    }
+}
+
+pub(crate) fn signal_ref(msg: &Message, signal: &rs_dbc::Signal) -> ObjectRef {
+    ObjectRef::Signal(file_message_id(msg), signal.name().to_string())
+}
+
+type MessagePair<'a> = (Option<&'a Message>, Option<&'a Message>);
+
+/// Pairs the messages of both files according to the match mode.
+///
+/// Unpaired messages get `None` on the other side. Pairs are sorted
+/// alphabetically by the message name in DBC1, or in DBC2 for messages
+/// only it has.
+fn pair_messages<'a>(dbc1: &'a Dbc, dbc2: &'a Dbc, match_mode: MatchMode) -> Vec<MessagePair<'a>> {
+    let mut pairs = Vec::new();
+    let mut unpaired1: Vec<&Message> = dbc1.messages.iter().collect();
+    let mut unpaired2: Vec<&Message> = dbc2.messages.iter().collect();
+
+    if match_mode != MatchMode::Name {
+        (unpaired1, unpaired2) = pair_messages_by(&mut pairs, unpaired1, unpaired2, |msg| msg.message_id().0);
+    }
+    if match_mode != MatchMode::Id {
+        (unpaired1, unpaired2) = pair_messages_by(&mut pairs, unpaired1, unpaired2, |msg| msg.message_name().to_string());
+    }
+
+    pairs.extend(unpaired1.into_iter().map(|msg| (Some(msg), None)));
+    pairs.extend(unpaired2.into_iter().map(|msg| (None, Some(msg))));
+
+    // Sort message names alphabetically
+    pairs.sort_by(|a, b| pair_name(a).cmp(pair_name(b)));
+    pairs
+}
+
+/// Pairs messages with equal keys and returns the messages left unpaired.
+fn pair_messages_by<'a, K: Eq + Hash>(
+    pairs: &mut Vec<MessagePair<'a>>,
+    unpaired1: Vec<&'a Message>,
+    unpaired2: Vec<&'a Message>,
+    key: impl Fn(&Message) -> K,
+) -> (Vec<&'a Message>, Vec<&'a Message>) {
+    let mut index2: HashMap<K, usize> = HashMap::new();
+    for (index, msg) in unpaired2.iter().enumerate() {
+        index2.entry(key(msg)).or_insert(index);
+    }
+
+    let mut taken = vec![false; unpaired2.len()];
+    let mut rest1 = Vec::new();
+    for msg1 in unpaired1 {
+        match index2.get(&key(msg1)) {
+            Some(&index) if !taken[index] => {
+                taken[index] = true;
+                pairs.push((Some(msg1), Some(unpaired2[index])));
+            },
+            _ => rest1.push(msg1),
+        }
+    }
+
+    let rest2 = unpaired2
+        .into_iter()
+        .zip(taken)
+        .filter(|(_, taken)| !taken)
+        .map(|(msg, _)| msg)
+        .collect();
+    (rest1, rest2)
+}
+
+fn pair_name<'a>(pair: &MessagePair<'a>) -> &'a str {
+    match pair {
+        (Some(msg), _) | (None, Some(msg)) => msg.message_name(),
+        (None, None) => "",
+    }
+}
+
+fn compare_message_properties_for_results(results: &mut Vec<ComparisonResult>, pair: &PairedMessages) {
+    let (msg1, msg2) = (pair.msg1, pair.msg2);
+    let msg_name = msg1.message_name();
+
+    // Report messages paired by ID under a different name as a rename
+    if msg1.message_name() != msg2.message_name() {
+        results.push(ComparisonResult::renamed(EntityKind::Message, msg_name, None, msg2.message_name()));
+    }
+
+    let mut push = |field: Field, dbc1: Option<Value>, dbc2: Option<Value>| {
+        results.push(ComparisonResult::new(EntityKind::Message, msg_name, None, field, dbc1, dbc2));
+    };
+
+    // Compare message size
+    if msg1.message_size() != msg2.message_size() {
+        push(Field::Dlc, Some(Value::Integer(msg1.message_size())), Some(Value::Integer(msg2.message_size())));
+    }
+
+    // Compare cycle time
+    if msg1.cycle_time() != msg2.cycle_time() {
+        push(
+            Field::CycleTime,
+            Some(Value::Integer(msg1.cycle_time().into())),
+            Some(Value::Integer(msg2.cycle_time().into())),
+        );
+    }
+
+    // Compare transmitters, reporting each added or removed sender
+    let transmitters1 = transmitters(pair.dbc1, msg1);
+    let transmitters2 = transmitters(pair.dbc2, msg2);
+    let mut all_transmitters: Vec<&String> = transmitters1.iter().chain(&transmitters2).collect();
+    all_transmitters.sort();
+    all_transmitters.dedup();
+    for node in all_transmitters {
+        let sender = Some(Value::Text(node.clone()));
+        match (transmitters1.contains(node), transmitters2.contains(node)) {
+            (true, false) => push(Field::Transmitter, sender, None),
+            (false, true) => push(Field::Transmitter, None, sender),
+            _ => {},
+        }
+    }
+
+    // Compare message ID
+    let (id1, _) = msg1.message_id();
+    let (id2, _) = msg2.message_id();
+    if id1 != id2 {
+        push(Field::MessageId, Some(Value::Id(id1)), Some(Value::Id(id2)));
+    }
+
+    // Compare message ID kind
+    let kind1 = IdFormat::from_dbc(&msg1.message_id);
+    let kind2 = IdFormat::from_dbc(&msg2.message_id);
+    if kind1 != kind2 {
+        push(Field::IdFormat, Some(Value::IdFormat(kind1)), Some(Value::IdFormat(kind2)));
+    }
+
+    // Compare signal groups
+    compare_signal_groups_for_results(results, pair);
+
+    // Compare attributes and comment
+    let target1 = ObjectRef::Message(file_message_id(msg1));
+    let target2 = ObjectRef::Message(file_message_id(msg2));
+    let make_result =
+        |field, value1, value2| ComparisonResult::new(EntityKind::Message, msg_name, None, field, value1, value2);
+    compare_attribute_values_for_results(
+        results,
+        (&pair.dbc1.network, &target1),
+        (&pair.dbc2.network, &target2),
+        make_result,
+    );
+    compare_comments_for_results(
+        results,
+        (&pair.dbc1.network, &target1),
+        (&pair.dbc2.network, &target2),
+        &pair.options.text_matching,
+        make_result,
+    );
+}
+
+fn compare_signal_groups_for_results(results: &mut Vec<ComparisonResult>, pair: &PairedMessages) {
+    let msg_name = pair.msg1.message_name();
+    let no_groups = BTreeMap::new();
+    let groups1 = pair.dbc1.network.signal_groups.get(&file_message_id(pair.msg1)).unwrap_or(&no_groups);
+    let groups2 = pair.dbc2.network.signal_groups.get(&file_message_id(pair.msg2)).unwrap_or(&no_groups);
+
+    for (name, members1, members2) in pair_by_key(groups1, groups2) {
+        // Member order does not matter, so compare sorted member lists
+        let members1 = members1.map(|signals| sorted(signals.clone()));
+        let members2 = members2.map(|signals| sorted(signals.clone()));
+        if members1 != members2 {
+            results.push(ComparisonResult::new(
+                EntityKind::Message,
+                msg_name,
+                None,
+                Field::SignalGroup(name.clone()),
+                members1.map(Value::List),
+                members2.map(Value::List),
+            ));
+        }
+    }
+}
+
+fn compare_signals_for_results(results: &mut Vec<ComparisonResult>, pair: &PairedMessages) {
+    let (msg1, msg2) = (pair.msg1, pair.msg2);
+    let msg_name = msg1.message_name();
+
+    // Create maps for signal lookup, sorted by signal name
+    let mut signals1: BTreeMap<&str, &rs_dbc::Signal> = BTreeMap::new();
+    let mut signals2: BTreeMap<&str, &rs_dbc::Signal> = BTreeMap::new();
+
+    for signal in &msg1.signals {
+        signals1.insert(signal.name(), signal);
+    }
+
+    for signal in &msg2.signals {
+        signals2.insert(signal.name(), signal);
+    }
+
+    // Pair signals that only exist on one side when they look like a rename
+    let removed: Vec<&rs_dbc::Signal> = msg1.signals.iter().filter(|s| !signals2.contains_key(s.name())).collect();
+    let added: Vec<&rs_dbc::Signal> = msg2.signals.iter().filter(|s| !signals1.contains_key(s.name())).collect();
+    let mut renames: HashMap<&str, (&rs_dbc::Signal, f64)> = HashMap::new();
+    let mut rename_targets: HashSet<&str> = HashSet::new();
+    for (s1, s2, confidence) in propose_signal_renames(&removed, &added) {
+        renames.insert(s1.name(), (s2, confidence));
+        rename_targets.insert(s2.name());
+    }
+
+    for (&signal_name, sig1, sig2) in pair_by_key(&signals1, &signals2) {
+        let start = results.len();
+        match (sig1.copied(), sig2.copied()) {
+            (Some(s1), Some(s2)) => {
+                // Both DBCs have this signal - compare properties
+                compare_signal_properties_for_results(results, pair, s1, s2);
+                tag_mux_page(&mut results[start..], mux_page(pair, s1, s2));
+            },
+            (Some(s1), None) if renames.contains_key(signal_name) => {
+                // DBC2 has this signal under a similar name
+                let (s2, confidence) = renames[signal_name];
+                results.push(
+                    ComparisonResult::renamed(EntityKind::Signal, msg_name, Some(s1.name()), s2.name())
+                        .with_detail(Detail::Confidence(confidence)),
+                );
+                compare_signal_properties_for_results(results, pair, s1, s2);
+                tag_mux_page(&mut results[start..], mux_page(pair, s1, s2));
+            },
+            (None, Some(_)) if rename_targets.contains(signal_name) => {
+                // Already reported as the new name of a renamed signal
+            },
+            (Some(s1), None) => {
+                // Only DBC1 has this signal
+                let page = pair.dbc1.network.multiplexing_of(&signal_ref(pair.msg1, s1)).multiplexor_value;
+                results.push(
+                    ComparisonResult::exists(EntityKind::Signal, msg_name, Some(s1.name()), ChangeKind::Removed)
+                        .with_mux_page(page),
+                );
+            },
+            (None, Some(s2)) => {
+                // Only DBC2 has this signal
+                let page = pair.dbc2.network.multiplexing_of(&signal_ref(pair.msg2, s2)).multiplexor_value;
+                results.push(
+                    ComparisonResult::exists(EntityKind::Signal, msg_name, Some(s2.name()), ChangeKind::Added)
+                        .with_mux_page(page),
+                );
+            },
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+/// Physical value range the raw bits of a signal can represent, from its
+/// length, signedness, factor and offset. IEEE float and double signals
+/// have no fixed range and return `None`.
+pub(crate) fn physical_range(sig: &rs_dbc::Signal, extended_type: ExtendedValueType) -> Option<(f64, f64)> {
+    if extended_type != ExtendedValueType::Integer {
+        return None;
+    }
+
+    let length = sig.signal_size() as i32;
+    let (raw_min, raw_max) = match ValueType::from_dbc(sig.value_type()) {
+        ValueType::Unsigned => (0.0, 2f64.powi(length) - 1.0),
+        ValueType::Signed if length > 0 => (-(2f64.powi(length - 1)), 2f64.powi(length - 1) - 1.0),
+        ValueType::Signed => (0.0, 0.0),
+    };
+
+    let bound1 = raw_min * sig.factor() + sig.offset();
+    let bound2 = raw_max * sig.factor() + sig.offset();
+    Some((bound1.min(bound2), bound1.max(bound2)))
+}
+
+/// Checks whether the declared `[min|max]` of a signal fits its physical range.
+///
+/// Declared bounds may overshoot by up to half a raw step, which is
+/// rounding when the bounds were computed from the factor.
+pub(crate) fn range_fit(sig: &rs_dbc::Signal, physical: Option<(f64, f64)>) -> RangeFit {
+    if sig.min() == 0.0 && sig.max() == 0.0 {
+        return RangeFit::Unspecified;
+    }
+    let Some((min, max)) = physical else {
+        return RangeFit::Fits;
+    };
+
+    let slack = sig.factor().abs() / 2.0;
+    if sig.min() >= min - slack && sig.max() <= max + slack {
+        RangeFit::Fits
+    } else {
+        RangeFit::Exceeds
+    }
+}
+
+/// Multiplexor value of the page a paired signal is on, preferring DBC2.
+fn mux_page(pair: &PairedMessages, sig1: &rs_dbc::Signal, sig2: &rs_dbc::Signal) -> Option<u64> {
+    let page1 = pair.dbc1.network.multiplexing_of(&signal_ref(pair.msg1, sig1)).multiplexor_value;
+    let page2 = pair.dbc2.network.multiplexing_of(&signal_ref(pair.msg2, sig2)).multiplexor_value;
+    page2.or(page1)
+}
+
+/// Notes on float results the tolerance they were compared with, when it is
+/// not the default.
+fn note_tolerances(results: &mut [ComparisonResult], tolerances: &Tolerances) {
+    for result in results {
+        match tolerances.for_field(&result.field) {
+            Some(tolerance) if tolerance != Tolerance::default() => result.details.push(Detail::Tolerance(tolerance)),
+            _ => {},
+        }
+    }
+}
+
+/// Tags results with the multiplexor value of the page their signal is on.
+fn tag_mux_page(results: &mut [ComparisonResult], page: Option<u64>) {
+    for result in results {
+        result.mux_page = page;
+    }
+}
+
+/// Proposes renames between signals only in DBC1 and signals only in DBC2.
+///
+/// Each candidate pair is scored from the name similarity (weighted 70%)
+/// and whether start bit and length match (15% each), so a pair needs a
+/// very similar name and one matching property, or a loosely similar name
+/// and both. Pairs scoring at least [`RENAME_CONFIDENCE_THRESHOLD`] are
+/// accepted greedily, best first, so each signal takes part in at most one
+/// rename.
+fn propose_signal_renames<'a>(
+    removed: &[&'a rs_dbc::Signal],
+    added: &[&'a rs_dbc::Signal],
+) -> Vec<(&'a rs_dbc::Signal, &'a rs_dbc::Signal, f64)> {
+    let mut candidates = Vec::new();
+    for &s1 in removed {
+        for &s2 in added {
+            let mut confidence = 0.7 * calculate_similarity(s1.name(), s2.name());
+            if s1.start_bit() == s2.start_bit() {
+                confidence += 0.15;
+            }
+            if s1.signal_size() == s2.signal_size() {
+                confidence += 0.15;
+            }
+            if confidence >= RENAME_CONFIDENCE_THRESHOLD {
+                candidates.push((s1, s2, confidence));
+            }
+        }
+    }
+
+    // Best candidates first; ties keep a stable order by name
+    candidates.sort_by(|a, b| {
+        b.2.total_cmp(&a.2)
+            .then_with(|| a.0.name().cmp(b.0.name()))
+            .then_with(|| a.1.name().cmp(b.1.name()))
+    });
+
+    let mut used1: HashSet<&str> = HashSet::new();
+    let mut used2: HashSet<&str> = HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|(s1, s2, _)| {
+            if used1.contains(s1.name()) || used2.contains(s2.name()) {
+                return false;
+            }
+            used1.insert(s1.name());
+            used2.insert(s2.name());
+            true
+        })
+        .collect()
+}
+
+fn compare_signal_properties_for_results(
+    results: &mut Vec<ComparisonResult>,
+    pair: &PairedMessages,
+    sig1: &rs_dbc::Signal,
+    sig2: &rs_dbc::Signal
+) {
+    let msg_name = pair.msg1.message_name();
+    let signal_name = sig1.name();
+    let tolerances = &pair.options.tolerances;
+    let mut push = |field: Field, dbc1: Option<Value>, dbc2: Option<Value>| {
+        results.push(ComparisonResult::new(EntityKind::Signal, msg_name, Some(signal_name), field, dbc1, dbc2));
+    };
+
+    // Compare the payload bits the signal occupies. When they match, a
+    // different start bit or byte order is only a different numbering; when
+    // they differ, this one row stands for the new start bit and byte order.
+    let bits1 = signal_bits(sig1);
+    let bits2 = signal_bits(sig2);
+    if bits1 != bits2 {
+        push(
+            Field::Layout,
+            Some(Value::IntegerRanges(bit_runs(&bits1))),
+            Some(Value::IntegerRanges(bit_runs(&bits2))),
+        );
+    }
+
+    // Compare signal size
+    if sig1.signal_size() != sig2.signal_size() {
+        push(Field::Length, Some(Value::Integer(sig1.signal_size())), Some(Value::Integer(sig2.signal_size())));
+    }
+
+    // Compare factor
+    if tolerances.factor.differs(sig1.factor(), sig2.factor()) {
+        push(Field::Factor, Some(Value::Float(sig1.factor())), Some(Value::Float(sig2.factor())));
+    }
+
+    // Compare offset
+    if tolerances.offset.differs(sig1.offset(), sig2.offset()) {
+        push(Field::Offset, Some(Value::Float(sig1.offset())), Some(Value::Float(sig2.offset())));
+    }
+
+    // Compare min value
+    if tolerances.min.differs(sig1.min(), sig2.min()) {
+        push(Field::MinValue, Some(Value::Float(sig1.min())), Some(Value::Float(sig2.min())));
+    }
+
+    // Compare max value
+    if tolerances.max.differs(sig1.max(), sig2.max()) {
+        push(Field::MaxValue, Some(Value::Float(sig1.max())), Some(Value::Float(sig2.max())));
+    }
+
+    // Compare unit
+    let unit1 = text_value(sig1.unit());
+    let unit2 = text_value(sig2.unit());
+    if unit1 != unit2 {
+        push(Field::Unit, unit1, unit2);
+    }
+
+    // Compare value type
+    if sig1.value_type() != sig2.value_type() {
+        push(
+            Field::ValueType,
+            Some(Value::ValueType(ValueType::from_dbc(sig1.value_type()))),
+            Some(Value::ValueType(ValueType::from_dbc(sig2.value_type()))),
+        );
+    }
+
+    // Compare extended value type
+    let target1 = signal_ref(pair.msg1, sig1);
+    let target2 = signal_ref(pair.msg2, sig2);
+    let extended_type1 = pair.dbc1.network.value_type_of(&target1);
+    let extended_type2 = pair.dbc2.network.value_type_of(&target2);
+    if extended_type1 != extended_type2 {
+        push(
+            Field::ExtendedValueType,
+            Some(Value::ExtendedValueType(extended_type1)),
+            Some(Value::ExtendedValueType(extended_type2)),
+        );
+    }
+
+    // Compare the physical range the raw bits can represent
+    let physical1 = physical_range(sig1, extended_type1);
+    let physical2 = physical_range(sig2, extended_type2);
+    let physical_different = match (physical1, physical2) {
+        (Some((min1, max1)), Some((min2, max2))) => {
+            tolerances.min.differs(min1, min2) || tolerances.max.differs(max1, max2)
+        },
+        (physical1, physical2) => physical1.is_some() != physical2.is_some(),
+    };
+    if physical_different {
+        push(
+            Field::PhysicalRange,
+            physical1.map(|(min, max)| Value::Range(min, max)),
+            physical2.map(|(min, max)| Value::Range(min, max)),
+        );
+    }
+
+    // Check the declared range still fits, when that changed or the new one does not fit
+    let fit1 = range_fit(sig1, physical1);
+    let fit2 = range_fit(sig2, physical2);
+    let declared_different =
+        tolerances.min.differs(sig1.min(), sig2.min()) || tolerances.max.differs(sig1.max(), sig2.max());
+    if fit1 != fit2 || (fit2 == RangeFit::Exceeds && (physical_different || declared_different)) {
+        push(Field::RangeFit, Some(Value::RangeFit(fit1)), Some(Value::RangeFit(fit2)));
+    }
+
+    // Compare receivers
+    if sig1.receivers() != sig2.receivers() {
+        push(Field::Receivers, receivers_value(sig1.receivers()), receivers_value(sig2.receivers()));
+    }
+
+    // Compare multiplexer type
+    if sig1.multiplexer_type() != sig2.multiplexer_type() {
+        push(
+            Field::MultiplexerType,
+            Some(Value::MultiplexerType(MultiplexerType::from_dbc(sig1.multiplexer_type()))),
+            Some(Value::MultiplexerType(MultiplexerType::from_dbc(sig2.multiplexer_type()))),
+        );
+    }
+
+    // Compare multiplexor value and multiplexor signal
+    let (network1, network2) = (&pair.dbc1.network, &pair.dbc2.network);
+    let mux_value1 = network1.multiplexing_of(&target1).multiplexor_value;
+    let mux_value2 = network2.multiplexing_of(&target2).multiplexor_value;
+    if mux_value1 != mux_value2 {
+        push(Field::MultiplexorValue, mux_value1.map(Value::Integer), mux_value2.map(Value::Integer));
+    }
+
+    let multiplexor1 = network1.multiplexor_of(file_message_id(pair.msg1), sig1.name());
+    let multiplexor2 = network2.multiplexor_of(file_message_id(pair.msg2), sig2.name());
+    if multiplexor1 != multiplexor2 {
+        push(
+            Field::MultiplexorSignal,
+            multiplexor1.map(|name| Value::Text(name.to_string())),
+            multiplexor2.map(|name| Value::Text(name.to_string())),
+        );
+    }
+
+    // Compare extended multiplexing ranges
+    let ranges1 = network1.extended_multiplexing.get(&target1).map(|mux| Value::IntegerRanges(mux.ranges.clone()));
+    let ranges2 = network2.extended_multiplexing.get(&target2).map(|mux| Value::IntegerRanges(mux.ranges.clone()));
+    if ranges1 != ranges2 {
+        push(Field::MultiplexorRanges, ranges1, ranges2);
+    }
+
+    // Compare initial values
+    let raw_initial1 = sig1.initial_value();
+    let raw_initial2 = sig2.initial_value();
+    let vector_initial1 = sig1.vector_initial_value();
+    let vector_initial2 = sig2.vector_initial_value();
+
+    let raw_initial_different = tolerances.initial.differs(raw_initial1, raw_initial2);
+    let vector_initial_different = tolerances.initial.differs(vector_initial1, vector_initial2);
+
+    // If Vector initial values are different, show Vector output only
+    if vector_initial_different {
+        push(Field::InitialValueVector, Some(Value::Float(vector_initial1)), Some(Value::Float(vector_initial2)));
+    }
+    // If Vector initial values are same but raw initial values are different, show raw output only
+    if raw_initial_different {
+        push(Field::InitialValueRaw, Some(Value::Float(raw_initial1)), Some(Value::Float(raw_initial2)));
+    }
+
+    // Compare value descriptions
+    compare_value_descriptions(
+        results,
+        msg_name,
+        signal_name,
+        (&pair.dbc1.network, &target1),
+        (&pair.dbc2.network, &target2),
+        &pair.options.text_matching,
+    );
+
+    // Compare attributes and comment
+    let make_result = |field, value1, value2| {
+        ComparisonResult::new(EntityKind::Signal, msg_name, Some(signal_name), field, value1, value2)
+    };
+    compare_attribute_values_for_results(
+        results,
+        (&pair.dbc1.network, &target1),
+        (&pair.dbc2.network, &target2),
+        make_result,
+    );
+    compare_comments_for_results(
+        results,
+        (&pair.dbc1.network, &target1),
+        (&pair.dbc2.network, &target2),
+        &pair.options.text_matching,
+        make_result,
+    );
+}
+
+fn compare_value_descriptions(
+    results: &mut Vec<ComparisonResult>,
+    msg_name: &str,
+    signal_name: &str,
+    (network1, target1): (&Network, &ObjectRef),
+    (network2, target2): (&Network, &ObjectRef),
+    matching: &TextMatching,
+) {
+    let val_desc1 = network1.value_descriptions_of(target1);
+    let val_desc2 = network2.value_descriptions_of(target2);
+
+    let make_result = |field, value1, value2| {
+        ComparisonResult::new(EntityKind::Signal, msg_name, Some(signal_name), field, value1, value2)
+    };
+    for (value, desc1, desc2) in pair_by_key(&val_desc1, &val_desc2) {
+        // Handle missing, empty or whitespace-only descriptions
+        let desc1 = desc1.and_then(|d| text_value(d));
+        let desc2 = desc2.and_then(|d| text_value(d));
+        compare_texts_for_results(results, Field::ValueDescription(*value), (desc1, desc2), matching, make_result);
+    }
+}
+
+/// Compares the comments on one object in each file.
+fn compare_comments_for_results(
+    results: &mut Vec<ComparisonResult>,
+    (network1, target1): (&Network, &ObjectRef),
+    (network2, target2): (&Network, &ObjectRef),
+    matching: &TextMatching,
+    make_result: impl Fn(Field, Option<Value>, Option<Value>) -> ComparisonResult,
+) {
+    let comment1 = network1.comment_of(target1).and_then(text_value);
+    let comment2 = network2.comment_of(target2).and_then(text_value);
+    compare_texts_for_results(results, Field::Comment, (comment1, comment2), matching, make_result);
+}
+
+/// Compares two free texts and reports them when they differ enough.
+///
+/// Texts present on both sides are compared with `matching` and reported
+/// with their similarity when it is below its threshold. By default,
+/// whitespace, case and word order changes, and wording changes with a
+/// Jaro-Winkler similarity of at least 0.85, are not reported.
+fn compare_texts_for_results(
+    results: &mut Vec<ComparisonResult>,
+    field: Field,
+    (text1, text2): (Option<Value>, Option<Value>),
+    matching: &TextMatching,
+    make_result: impl Fn(Field, Option<Value>, Option<Value>) -> ComparisonResult,
+) {
+    // Only fuzzy strategies score a similarity worth showing
+    let similarity = match (&text1, &text2) {
+        (Some(Value::Text(t1)), Some(Value::Text(t2))) if matching.differs(t1, t2) => {
+            matching.strategy.is_fuzzy().then(|| matching.strategy.similarity(t1, t2))
+        },
+        (Some(Value::Text(_)), Some(Value::Text(_))) | (None, None) => return,
+        _ => None,
+    };
+
+    let result = make_result(field, text1, text2);
+    results.push(match similarity {
+        Some(similarity) => result.with_detail(Detail::Similarity(similarity)),
+        None => result,
+    });
+}
+
+/// Free text as a value, treating empty or whitespace-only text as no value.
+/// Pairs the entries of two maps by key, in key order. A key only one map
+/// has is paired with `None` on the other side.
+fn pair_by_key<'a, K: Ord, V>(
+    map1: &'a BTreeMap<K, V>,
+    map2: &'a BTreeMap<K, V>,
+) -> impl Iterator<Item = (&'a K, Option<&'a V>, Option<&'a V>)> {
+    let mut keys: Vec<&K> = map1.keys().chain(map2.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter().map(|key| (key, map1.get(key), map2.get(key)))
+}
+
+fn text_value(text: &str) -> Option<Value> {
+    if text.trim().is_empty() { None } else { Some(Value::Text(text.to_string())) }
+}
+
+fn sorted(mut items: Vec<String>) -> Vec<String> {
+    items.sort();
+    items
+}
+
+fn list_value(items: Vec<String>) -> Option<Value> {
+    if items.is_empty() { None } else { Some(Value::List(items)) }
+}
+
+/// All senders of a message: the `BO_` transmitter and any from `BO_TX_BU_`.
+fn transmitters(file: &DbcFile, msg: &Message) -> Vec<String> {
+    let additional = file.network.message_transmitters.get(&file_message_id(msg));
+    let mut transmitters: Vec<String> = std::iter::once(&msg.transmitter)
+        .chain(additional.into_iter().flatten())
+        .cloned()
+        .collect();
+    transmitters.sort();
+    transmitters.dedup();
+    transmitters
+}
+
+fn receivers_value(receivers: &[String]) -> Option<Value> {
+    list_value(receivers.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::AccessType;
+    use crate::parse_dbc;
+
+    const SEVERAL_MULTIPLEXORS: &str = "\
+VERSION \"\"
+
+NS_ :
+
+BS_:
+
+BU_: ECU PC
+
+BO_ 100 Muxed: 8 ECU
+ SG_ Mux M : 0|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Sub m1M : 8|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Sub2 m2M : 8|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Sub3 m3M : 8|8@1+ (1,0) [0|255] \"\" PC
+ SG_ A m1 : 16|8@1+ (1,0) [0|255] \"\" PC
+ SG_ B m2 : 24|8@1+ (1,0) [0|255] \"\" PC
+
+";
+
+    #[test]
+    fn file_with_several_multiplexors_equals_itself() {
+        for _ in 0..20 {
+            let dbc1 = parse_dbc(SEVERAL_MULTIPLEXORS.as_bytes()).unwrap();
+            let dbc2 = parse_dbc(SEVERAL_MULTIPLEXORS.as_bytes()).unwrap();
+            assert_eq!(compare_dbc_files(&dbc1, &dbc2).unwrap(), Vec::new());
+        }
+    }
+
+    const RENAMES: &str = "\
+VERSION \"\"
+
+BU_: ECU PC
+
+BO_ 100 Before: 8 ECU
+ SG_ EngineSpeed : 0|16@1+ (1,0) [0|65535] \"\" PC
+ SG_ Temperature : 16|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Pressure : 24|8@1+ (1,0) [0|255] \"\" PC
+
+BO_ 200 After: 8 ECU
+ SG_ EngineSpeedRaw : 0|12@1+ (1,0) [0|4095] \"\" PC
+ SG_ TemperatureC : 32|16@1+ (1,0) [0|65535] \"\" PC
+ SG_ Voltage : 24|8@1+ (1,0) [0|255] \"\" PC
+
+";
+
+    #[test]
+    fn renames_need_a_similar_name_and_a_matching_property() {
+        let file = parse_dbc(RENAMES.as_bytes()).unwrap();
+        let signals = |id: u32| -> Vec<&rs_dbc::Signal> {
+            let msg = file.dbc.messages.iter().find(|msg| file_message_id(msg) == id).unwrap();
+            msg.signals.iter().collect()
+        };
+
+        let renames: Vec<(&str, &str)> = propose_signal_renames(&signals(100), &signals(200))
+            .into_iter()
+            .map(|(s1, s2, _)| (s1.name(), s2.name()))
+            .collect();
+
+        // A resized signal keeps its start bit; a moved and resized one has
+        // no matching property, and matching bits alone do not make a rename
+        assert_eq!(renames, vec![("EngineSpeed", "EngineSpeedRaw")]);
+    }
+
+    /// A file with one message holding `signal`, an `SG_` line without its name.
+    fn file_with_signal(signal: &str) -> DbcFile {
+        let text = format!("VERSION \"\"\n\nBU_: ECU PC\n\nBO_ 100 Msg: 8 ECU\n SG_ Sig : {} \"\" PC\n\n", signal);
+        parse_dbc(text.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn moving_a_bit_is_a_layout_change() {
+        let dbc1 = file_with_signal("6|1@0+ (1,0) [0|1]");
+        let dbc2 = file_with_signal("1|1@1+ (1,0) [0|1]");
+        let results = compare_dbc_files(&dbc1, &dbc2).unwrap();
+
+        // The new start bit and byte order are part of the one layout row
+        assert_eq!(results.len(), 1, "{:?}", results);
+        assert_eq!(results[0].field, Field::Layout);
+        assert_eq!(results[0].dbc1, Some(Value::IntegerRanges(vec![(6, 6)])));
+        assert_eq!(results[0].dbc2, Some(Value::IntegerRanges(vec![(1, 1)])));
+    }
+
+    #[test]
+    fn renumbering_the_same_bits_is_not_a_difference() {
+        let dbc1 = file_with_signal("7|8@0+ (1,0) [0|255]");
+        let dbc2 = file_with_signal("0|8@1+ (1,0) [0|255]");
+        let results = compare_dbc_files(&dbc1, &dbc2).unwrap();
+
+        assert!(results.is_empty(), "{:?}", results);
+    }
+
+    /// Physical range and declared range fit of the signal of `file_with_signal(signal)`.
+    fn fit_of(signal: &str, extended_type: ExtendedValueType) -> (Option<(f64, f64)>, RangeFit) {
+        let file = file_with_signal(signal);
+        let sig = &file.dbc.messages[0].signals[0];
+        let physical = physical_range(sig, extended_type);
+        (physical, range_fit(sig, physical))
+    }
+
+    #[test]
+    fn declared_ranges_within_the_raw_range_fit() {
+        let integer = ExtendedValueType::Integer;
+        assert_eq!(fit_of("23|10@0+ (1,0) [0|1023]", integer), (Some((0.0, 1023.0)), RangeFit::Fits));
+        assert_eq!(fit_of("0|8@1+ (0.5,-10) [-10|117.5]", integer), (Some((-10.0, 117.5)), RangeFit::Fits));
+        assert_eq!(fit_of("0|8@1+ (1,0) [0|256]", integer).1, RangeFit::Exceeds);
+        assert_eq!(fit_of("0|8@1+ (1,0) [0|0]", integer).1, RangeFit::Unspecified);
+    }
+
+    #[test]
+    fn declared_ranges_may_overshoot_by_half_a_step() {
+        let integer = ExtendedValueType::Integer;
+        assert_eq!(fit_of("0|8@1+ (2,0) [0|511]", integer).1, RangeFit::Fits);
+        assert_eq!(fit_of("0|8@1+ (2,0) [-1|510]", integer).1, RangeFit::Fits);
+        assert_eq!(fit_of("0|8@1+ (2,0) [0|511.5]", integer).1, RangeFit::Exceeds);
+        assert_eq!(fit_of("0|8@1+ (2,0) [-1.5|510]", integer).1, RangeFit::Exceeds);
+    }
+
+    #[test]
+    fn signed_signals_range_around_zero() {
+        let integer = ExtendedValueType::Integer;
+        assert_eq!(fit_of("0|8@1- (1,0) [-128|127]", integer), (Some((-128.0, 127.0)), RangeFit::Fits));
+        assert_eq!(fit_of("0|8@1- (1,0) [-129|127]", integer).1, RangeFit::Exceeds);
+        // A negative factor swaps the bounds
+        assert_eq!(fit_of("0|8@1- (-1,0) [-127|128]", integer), (Some((-127.0, 128.0)), RangeFit::Fits));
+    }
+
+    #[test]
+    fn float_signals_have_no_raw_range() {
+        assert_eq!(fit_of("0|32@1- (1,0) [-1E+38|1E+38]", ExtendedValueType::Float), (None, RangeFit::Fits));
+        assert_eq!(fit_of("0|64@1- (1,0) [-1E+300|1E+300]", ExtendedValueType::Double), (None, RangeFit::Fits));
+    }
+
+    #[test]
+    fn declaring_a_range_that_fits_is_reported() {
+        let dbc1 = file_with_signal("23|16@0+ (1,0) [0|0]");
+        let dbc2 = file_with_signal("23|10@0+ (1,0) [0|1023]");
+        let results = compare_dbc_files(&dbc1, &dbc2).unwrap();
+
+        let fit = results.iter().find(|result| result.field == Field::RangeFit).unwrap();
+        assert_eq!(fit.dbc1, Some(Value::RangeFit(RangeFit::Unspecified)));
+        assert_eq!(fit.dbc2, Some(Value::RangeFit(RangeFit::Fits)));
+        assert_eq!(crate::report::render(fit).dbc2, "Fits Raw Range");
+    }
+
+    const VALUE_TABLE: &str = "\
+VERSION \"\"
+
+BU_: ECU PC
+
+VAL_TABLE_ Modes 1 \"On\" 0 \"Off\" -1 \"Error\" ;
+
+BO_ 100 Msg: 8 ECU
+ SG_ Mode : 0|8@1- (1,0) [-128|127] \"\" PC
+ SG_ Backup : 8|8@1- (1,0) [-128|127] \"\" PC
+
+VAL_ 100 Mode 1 \"On\" 0 \"Off\" -1 \"Error\" ;
+";
+
+    #[test]
+    fn value_table_changes_name_the_signals_using_the_table() {
+        let dbc1 = parse_dbc(VALUE_TABLE.as_bytes()).unwrap();
+        let text2 = VALUE_TABLE.replace("-1 \"Error\"", "-1 \"Fault\"")
+            + "VAL_ 100 Backup 1 \"On\" 0 \"Off\" -1 \"Fault\" ;\n";
+        let dbc2 = parse_dbc(text2.as_bytes()).unwrap();
+        let results = compare_dbc_files(&dbc1, &dbc2).unwrap();
+        let table_results: Vec<&ComparisonResult> =
+            results.iter().filter(|result| result.entity == EntityKind::ValueTable).collect();
+
+        assert_eq!(table_results.len(), 2, "{:?}", table_results);
+        let reworded = table_results[0];
+        assert_eq!(reworded.field, Field::ValueDescription(-1));
+        assert_eq!(crate::report::render(reworded).field, "Value -0x1 Description");
+        assert_eq!(reworded.dbc1, Some(Value::Text("Error".to_string())));
+        assert_eq!(reworded.dbc2, Some(Value::Text("Fault".to_string())));
+        let users = vec!["Msg.Backup".to_string(), "Msg.Mode".to_string()];
+        assert!(reworded.details.contains(&Detail::ReferencedBy(users)));
+
+        let referenced = table_results[1];
+        assert_eq!(referenced.field, Field::ReferencedBy);
+        assert_eq!(referenced.dbc1, Some(Value::List(vec!["Msg.Mode".to_string()])));
+        assert_eq!(referenced.dbc2, Some(Value::List(vec!["Msg.Backup".to_string(), "Msg.Mode".to_string()])));
+
+        // The signal's own descriptions keep the sign of the raw value too
+        assert!(results.iter().any(|result| {
+            result.signal.as_deref() == Some("Mode") && result.field == Field::ValueDescription(-1)
+        }));
+    }
+
+    /// A file with the given `BO_` lines, each message holding one byte signal.
+    fn file_with_messages(messages: &[(u32, &str)]) -> DbcFile {
+        let mut text = "VERSION \"\"\n\nBU_: ECU PC\n\n".to_string();
+        for (id, name) in messages {
+            text += &format!("BO_ {} {}: 8 ECU\n SG_ Sig : 0|8@1+ (1,0) [0|255] \"\" PC\n\n", id, name);
+        }
+        parse_dbc(text.as_bytes()).unwrap()
+    }
+
+    /// Message pairs as (DBC1 name, DBC2 name), empty for the missing side.
+    fn pair_names(dbc1: &DbcFile, dbc2: &DbcFile, match_mode: MatchMode) -> Vec<(String, String)> {
+        let name = |msg: Option<&Message>| msg.map_or(String::new(), |msg| msg.message_name().to_string());
+        pair_messages(&dbc1.dbc, &dbc2.dbc, match_mode)
+            .into_iter()
+            .map(|(msg1, msg2)| (name(msg1), name(msg2)))
+            .collect()
+    }
+
+    fn pairs(names: &[(&str, &str)]) -> Vec<(String, String)> {
+        names.iter().map(|(name1, name2)| (name1.to_string(), name2.to_string())).collect()
+    }
+
+    #[test]
+    fn messages_pair_by_id_name_or_id_then_name() {
+        let dbc1 = file_with_messages(&[(100, "Engine"), (200, "Brake"), (300, "Door")]);
+        let dbc2 = file_with_messages(&[(100, "EngineData"), (250, "Brake"), (300, "Window"), (400, "Door")]);
+
+        assert_eq!(
+            pair_names(&dbc1, &dbc2, MatchMode::Id),
+            pairs(&[("Brake", ""), ("", "Brake"), ("Door", "Window"), ("", "Door"), ("Engine", "EngineData")]),
+        );
+        assert_eq!(
+            pair_names(&dbc1, &dbc2, MatchMode::Name),
+            pairs(&[("Brake", "Brake"), ("Door", "Door"), ("Engine", ""), ("", "EngineData"), ("", "Window")]),
+        );
+        // IDs are paired first, so Door keeps ID 300 and only Brake falls back to its name
+        assert_eq!(
+            pair_names(&dbc1, &dbc2, MatchMode::IdThenName),
+            pairs(&[("Brake", "Brake"), ("Door", "Window"), ("", "Door"), ("Engine", "EngineData")]),
+        );
+    }
+
+    #[test]
+    fn paired_messages_report_a_new_name_or_id() {
+        let dbc1 = file_with_messages(&[(100, "Engine"), (200, "Brake")]);
+        let dbc2 = file_with_messages(&[(100, "EngineData"), (250, "Brake")]);
+        let results = compare_dbc_files(&dbc1, &dbc2).unwrap();
+        let fields: Vec<(&str, &Field)> =
+            results.iter().map(|result| (result.message.as_deref().unwrap_or(""), &result.field)).collect();
+
+        assert_eq!(fields, vec![("Brake", &Field::MessageId), ("Engine", &Field::Name)]);
+        assert_eq!(find_message_pair(&dbc1, &dbc2, "Engine", MatchMode::Name).1, None);
+        let (_, msg2) = find_message_pair(&dbc1, &dbc2, "Engine", MatchMode::IdThenName);
+        assert_eq!(msg2.map(|msg| msg.message_name()), Some("EngineData"));
+    }
+
+    const ATTRIBUTES: &str = "\
+VERSION \"\"
+
+BU_: ECU PC
+
+BO_ 100 Msg: 8 ECU
+ SG_ Sig : 0|8@1+ (1,0) [0|255] \"\" PC
+
+BA_DEF_ BO_ \"Priority\" INT 0 7;
+BA_DEF_ SG_ \"GenSigSendType\" ENUM \"Cyclic\",\"OnChange\";
+BA_DEF_ \"BusType\" STRING ;
+BA_DEF_DEF_ \"Priority\" 3;
+BA_DEF_DEF_ \"BusType\" \"CAN\";
+BA_ \"Priority\" BO_ 100 5;
+BA_ \"GenSigSendType\" SG_ 100 Sig 0;
+BA_ \"BusType\" \"CAN FD\";
+";
+
+    #[test]
+    fn attribute_definitions_and_values_are_compared() {
+        let dbc1 = parse_dbc(ATTRIBUTES.as_bytes()).unwrap();
+        let text2 = ATTRIBUTES
+            .replace("BA_DEF_ \"BusType\" STRING ;\n", "BA_DEF_ SG_ \"Legacy\" STRING ;\n")
+            .replace("BA_DEF_DEF_ \"BusType\" \"CAN\";\n", "")
+            .replace("BA_ \"BusType\" \"CAN FD\";\n", "")
+            .replace("INT 0 7", "INT 0 15")
+            .replace("BO_ 100 5", "BO_ 100 6")
+            .replace("Sig 0;", "Sig 1;");
+        let dbc2 = parse_dbc(text2.as_bytes()).unwrap();
+
+        let text = |text: &str| Some(Value::Text(text.to_string()));
+        let priority = Field::Attribute("Priority".to_string());
+        let send_type = Field::Attribute("GenSigSendType".to_string());
+        assert_eq!(
+            compare_dbc_files(&dbc1, &dbc2).unwrap(),
+            vec![
+                ComparisonResult::object_exists(EntityKind::Attribute, "BusType", ChangeKind::Removed),
+                ComparisonResult::object_exists(EntityKind::Attribute, "Legacy", ChangeKind::Added),
+                ComparisonResult::for_object(
+                    EntityKind::Attribute,
+                    "Priority",
+                    Field::AttributeRange,
+                    Some(Value::Range(0.0, 7.0)),
+                    Some(Value::Range(0.0, 15.0)),
+                ),
+                ComparisonResult::for_network(Field::Attribute("BusType".to_string()), text("CAN FD"), None),
+                ComparisonResult::new(
+                    EntityKind::Message,
+                    "Msg",
+                    None,
+                    priority,
+                    Some(Value::Float(5.0)),
+                    Some(Value::Float(6.0)),
+                ),
+                // Enum values are reported by their label, not their index
+                ComparisonResult::new(
+                    EntityKind::Signal,
+                    "Msg",
+                    Some("Sig"),
+                    send_type,
+                    text("Cyclic"),
+                    text("OnChange"),
+                ),
+            ],
+        );
+    }
+
+    const ENVIRONMENT_VARIABLES: &str = "\
+VERSION \"\"
+
+BU_: ECU PC
+
+BO_ 100 Msg: 8 ECU
+ SG_ Sig : 0|8@1+ (1,0) [0|255] \"\" PC
+
+EV_ Gear: 0 [0|5] \"\" 1 7 DUMMY_NODE_VECTOR3 ECU,PC;
+EV_ Blob: 0 [0|0] \"\" 0 8 DUMMY_NODE_VECTOR8000 Vector__XXX;
+ENVVAR_DATA_ Blob: 4;
+";
+
+    #[test]
+    fn environment_variables_are_compared() {
+        let dbc1 = parse_dbc(ENVIRONMENT_VARIABLES.as_bytes()).unwrap();
+        let text2 = ENVIRONMENT_VARIABLES
+            .replace("EV_ Blob: 0 [0|0] \"\" 0 8 DUMMY_NODE_VECTOR8000 Vector__XXX;\nENVVAR_DATA_ Blob: 4;\n", "")
+            .replace("[0|5] \"\" 1 7 DUMMY_NODE_VECTOR3 ECU,PC", "[0|6] \"gear\" 1 7 DUMMY_NODE_VECTOR1 ECU")
+            + "EV_ Mode: 0 [0|1] \"\" 0 9 DUMMY_NODE_VECTOR0 PC;\n";
+        let dbc2 = parse_dbc(text2.as_bytes()).unwrap();
+
+        let gear = |field, value1, value2| {
+            ComparisonResult::for_object(EntityKind::EnvironmentVariable, "Gear", field, value1, value2)
+        };
+        let nodes = |nodes: &[&str]| Some(Value::List(nodes.iter().map(|node| node.to_string()).collect()));
+        assert_eq!(
+            compare_dbc_files(&dbc1, &dbc2).unwrap(),
+            vec![
+                ComparisonResult::object_exists(EntityKind::EnvironmentVariable, "Blob", ChangeKind::Removed),
+                gear(Field::MaxValue, Some(Value::Float(5.0)), Some(Value::Float(6.0))),
+                gear(Field::Unit, None, Some(Value::Text("gear".to_string()))),
+                gear(
+                    Field::AccessType,
+                    Some(Value::AccessType(AccessType::ReadWrite)),
+                    Some(Value::AccessType(AccessType::Read)),
+                ),
+                gear(Field::AccessNodes, nodes(&["ECU", "PC"]), nodes(&["ECU"])),
+                ComparisonResult::object_exists(EntityKind::EnvironmentVariable, "Mode", ChangeKind::Added),
+            ],
+        );
+    }
+
+    const SENDERS: &str = "\
+VERSION \"\"
+
+BU_: ECU PC Gateway
+
+BO_ 100 Msg: 8 ECU
+ SG_ Sig : 0|8@1+ (1,0) [0|255] \"\" PC
+
+BO_TX_BU_ 100 : ECU,PC;
+";
+
+    #[test]
+    fn senders_are_added_and_removed_individually() {
+        let dbc1 = parse_dbc(SENDERS.as_bytes()).unwrap();
+        let dbc2 = parse_dbc(SENDERS.replace("ECU,PC;", "ECU,Gateway;").as_bytes()).unwrap();
+
+        let sender = |node: &str| Some(Value::Text(node.to_string()));
+        assert_eq!(
+            compare_dbc_files(&dbc1, &dbc2).unwrap(),
+            vec![
+                ComparisonResult::new(EntityKind::Message, "Msg", None, Field::Transmitter, None, sender("Gateway")),
+                ComparisonResult::new(EntityKind::Message, "Msg", None, Field::Transmitter, sender("PC"), None),
+            ],
+        );
+
+        // Naming a sender in BO_ or in BO_TX_BU_ makes no difference
+        let dbc2 = parse_dbc(SENDERS.replace("8 ECU", "8 PC").as_bytes()).unwrap();
+        assert_eq!(compare_dbc_files(&dbc1, &dbc2).unwrap(), Vec::new());
+    }
+}
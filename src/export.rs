@@ -0,0 +1,43 @@
+//! CSV export of DBC comparisons.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use crate::model::ComparisonResult;
+use crate::report::{render, value_headers};
+
+/// Writes already computed comparison results to a CSV file at `path`.
+///
+/// The files are not parsed again, so the export is exactly the set of
+/// results that was reviewed.
+pub fn export_comparison_to_csv(results: &[ComparisonResult], path: &str) -> Result<(), Box<dyn Error>> {
+    let mut csv_file = File::create(path)?;
+    write_results_csv(results, &mut csv_file)
+}
+
+/// Writes comparison results as CSV, one row per difference, to `writer`.
+pub fn write_results_csv(results: &[ComparisonResult], writer: &mut impl Write) -> Result<(), Box<dyn Error>> {
+    let (value1, value2) = value_headers(results);
+    writeln!(writer, "Type,Severity,Message,Signal,Field,{},{},Details", value1, value2)?;
+    for result in results {
+        let row = render(result);
+        writeln!(writer, "{},{},{},{},{},{},{},{}",
+                escape_csv_field(&row.result_type),
+                escape_csv_field(&row.severity),
+                escape_csv_field(&row.message),
+                escape_csv_field(&row.signal),
+                escape_csv_field(&row.field),
+                escape_csv_field(&row.dbc1),
+                escape_csv_field(&row.dbc2),
+                escape_csv_field(&row.details))?;
+    }
+    Ok(())
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,353 @@
+//! Rules for ignoring differences that do not matter to a review.
+//!
+//! Rules are written as `[[ignore]]` tables in a TOML file:
+//!
+//! ```toml
+//! # Unit text is documentation only
+//! [[ignore]]
+//! field = "Unit"
+//!
+//! # Diagnostic messages change their cycle time freely
+//! [[ignore]]
+//! message = "Diag_*"
+//! field = "Cycle Time"
+//!
+//! # Test receivers come and go
+//! [[ignore]]
+//! field = "Receivers"
+//! value = "/^HIL_\\w+$/"
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::model::{ChangeKind, ComparisonResult, Value};
+
+/// A glob, such as `Diag_*`, or a regular expression between slashes, such
+/// as `/^Diag_\d+$/`, matched against whole names.
+///
+/// In globs `*` matches any run of characters and `?` a single character.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Pattern {
+    source: String,
+    regex: Regex,
+}
+
+impl Pattern {
+    /// Returns `true` when the pattern matches the whole of `text`.
+    pub fn matches(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let expression = match source.strip_prefix('/').and_then(|rest| rest.strip_suffix('/')) {
+            Some(expression) => format!("^(?:{})$", expression),
+            None => {
+                let glob: String = source
+                    .chars()
+                    .map(|c| match c {
+                        '*' => ".*".to_string(),
+                        '?' => ".".to_string(),
+                        c => regex::escape(&c.to_string()),
+                    })
+                    .collect();
+                format!("^{}$", glob)
+            },
+        };
+        let regex = Regex::new(&expression).map_err(|e| format!("Invalid pattern '{}': {}", source, e))?;
+        Ok(Pattern { source: source.to_string(), regex })
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = String;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        source.parse()
+    }
+}
+
+impl From<Pattern> for String {
+    fn from(pattern: Pattern) -> String {
+        pattern.source
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// A rule selecting differences to leave out of the results.
+///
+/// A rule applies to a result when every pattern it sets matches: `field`
+/// the field as shown in the Field column, such as `Unit`, `Cycle Time` or
+/// `Attribute GenMsgSendType`; `message` the message name, or the name of
+/// the node, attribute, value table or environment variable; and `signal`
+/// the signal name. A rule setting `signal` only applies to signal results.
+///
+/// Without `value`, the whole result is ignored. With `value`, only the
+/// names matching it are taken out of the values, such as one receiver in a
+/// receiver list, and the result is ignored when both sides are then equal.
+///
+/// A rule must set at least one pattern; an empty `[[ignore]]` table is
+/// rejected rather than ignoring every difference.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "IgnoreRuleTable")]
+pub struct IgnoreRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<Pattern>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<Pattern>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal: Option<Pattern>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Pattern>,
+}
+
+/// An `[[ignore]]` table as written, before it is checked to set a pattern.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IgnoreRuleTable {
+    #[serde(default)]
+    field: Option<Pattern>,
+    #[serde(default)]
+    message: Option<Pattern>,
+    #[serde(default)]
+    signal: Option<Pattern>,
+    #[serde(default)]
+    value: Option<Pattern>,
+}
+
+impl TryFrom<IgnoreRuleTable> for IgnoreRule {
+    type Error = String;
+
+    fn try_from(table: IgnoreRuleTable) -> Result<Self, Self::Error> {
+        let IgnoreRuleTable { field, message, signal, value } = table;
+        if field.is_none() && message.is_none() && signal.is_none() && value.is_none() {
+            return Err("Ignore rule sets none of field, message, signal or value, so it would ignore every difference"
+                .to_string());
+        }
+        Ok(IgnoreRule { field, message, signal, value })
+    }
+}
+
+impl IgnoreRule {
+    /// Returns `true` when the field, message and signal patterns of the
+    /// rule all match `result`.
+    pub fn applies_to(&self, result: &ComparisonResult) -> bool {
+        let matches = |pattern: &Option<Pattern>, text: Option<&str>| match (pattern, text) {
+            (None, _) => true,
+            (Some(pattern), Some(text)) => pattern.matches(text),
+            (Some(_), None) => false,
+        };
+        matches(&self.field, Some(&result.field.to_string()))
+            && matches(&self.message, result.message.as_deref().or(result.object.as_deref()))
+            && matches(&self.signal, result.signal.as_deref())
+    }
+}
+
+/// Matches `Vector__XXX`, the placeholder DBC editors write for "no node".
+pub fn placeholder_node() -> Pattern {
+    "Vector__XXX*".parse().unwrap()
+}
+
+/// Rules applied by default: the [`placeholder_node`] is never a real
+/// sender, receiver or accessing node.
+pub fn default_ignore_rules() -> Vec<IgnoreRule> {
+    let placeholder = placeholder_node();
+    ["Transmitter", "Receivers", "Access Nodes"]
+        .into_iter()
+        .map(|field| IgnoreRule {
+            field: Some(field.parse().unwrap()),
+            value: Some(placeholder.clone()),
+            ..IgnoreRule::default()
+        })
+        .collect()
+}
+
+/// Ignore rules as written in a rules or project file. Other tables and
+/// keys are allowed, so a project file can be used as a rules file.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct IgnoreRules {
+    /// Whether the [`default_ignore_rules`] apply as well.
+    #[serde(default = "default_true")]
+    pub defaults: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<IgnoreRule>,
+}
+
+impl Default for IgnoreRules {
+    fn default() -> Self {
+        IgnoreRules { defaults: true, ignore: Vec::new() }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl IgnoreRules {
+    /// Reads the rules from the TOML file at `path`.
+    pub fn load(path: &str) -> Result<IgnoreRules, Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+        Ok(toml::from_str(&text).map_err(|e| format!("Invalid rules file '{}': {}", path, e))?)
+    }
+
+    /// The rules to apply: the [`default_ignore_rules`], unless switched
+    /// off, followed by the `[[ignore]]` rules.
+    pub fn rules(&self) -> Vec<IgnoreRule> {
+        let mut rules = if self.defaults { default_ignore_rules() } else { Vec::new() };
+        rules.extend(self.ignore.iter().cloned());
+        rules
+    }
+}
+
+/// Reads the `[[ignore]]` rules from the TOML file at `path`.
+///
+/// The [`default_ignore_rules`] come first unless the file sets
+/// `defaults = false`.
+pub fn load_ignore_rules(path: &str) -> Result<Vec<IgnoreRule>, Box<dyn Error>> {
+    Ok(IgnoreRules::load(path)?.rules())
+}
+
+/// Removes the results, or the names within their values, that `rules`
+/// ignore.
+pub fn apply_ignore_rules(results: &mut Vec<ComparisonResult>, rules: &[IgnoreRule]) {
+    results.retain_mut(|result| {
+        for rule in rules {
+            if !rule.applies_to(result) {
+                continue;
+            }
+            let Some(value) = &rule.value else {
+                return false;
+            };
+            // Results without matching names, such as added or removed
+            // objects, are left alone
+            let dbc1 = without_names(result.dbc1.clone(), value);
+            let dbc2 = without_names(result.dbc2.clone(), value);
+            if dbc1 == result.dbc1 && dbc2 == result.dbc2 {
+                continue;
+            }
+            result.dbc1 = dbc1;
+            result.dbc2 = dbc2;
+            if result.dbc1 == result.dbc2 {
+                return false;
+            }
+            if matches!(result.change, ChangeKind::Added | ChangeKind::Removed | ChangeKind::Modified) {
+                result.change = ChangeKind::between(&result.dbc1, &result.dbc2);
+            }
+        }
+        true
+    });
+}
+
+/// A value without the names matching `pattern`, or `None` when no names
+/// are left.
+fn without_names(value: Option<Value>, pattern: &Pattern) -> Option<Value> {
+    match value? {
+        Value::Text(text) if pattern.matches(&text) => None,
+        Value::List(items) => {
+            let items: Vec<String> = items.into_iter().filter(|item| !pattern.matches(item)).collect();
+            if items.is_empty() { None } else { Some(Value::List(items)) }
+        },
+        value => Some(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{EntityKind, Field};
+
+    fn rules(source: &str) -> Vec<IgnoreRule> {
+        let rules: IgnoreRules = toml::from_str(source).unwrap();
+        rules.ignore
+    }
+
+    #[test]
+    fn globs_match_whole_names() {
+        let pattern: Pattern = "Diag_*".parse().unwrap();
+        assert!(pattern.matches("Diag_"));
+        assert!(pattern.matches("Diag_Request"));
+        assert!(!pattern.matches("EngineDiag_Request"));
+        assert!(!pattern.matches("diag_Request"));
+
+        let pattern: Pattern = "Msg?".parse().unwrap();
+        assert!(pattern.matches("Msg1"));
+        assert!(!pattern.matches("Msg"));
+        assert!(!pattern.matches("Msg12"));
+    }
+
+    #[test]
+    fn globs_escape_regex_characters() {
+        let pattern: Pattern = "Gen.Sig(1)".parse().unwrap();
+        assert!(pattern.matches("Gen.Sig(1)"));
+        assert!(!pattern.matches("GenXSig(1)"));
+    }
+
+    #[test]
+    fn regexes_between_slashes_match_whole_names() {
+        let pattern: Pattern = r"/HIL_\d+/".parse().unwrap();
+        assert!(pattern.matches("HIL_12"));
+        assert!(!pattern.matches("HIL_12a"));
+        assert!("/(/".parse::<Pattern>().is_err());
+    }
+
+    #[test]
+    fn value_rules_strip_names_from_lists() {
+        let mut results = vec![ComparisonResult::new(
+            EntityKind::Signal,
+            "Msg",
+            Some("Sig"),
+            Field::Receivers,
+            Some(Value::List(vec!["ECU".to_string(), "HIL_1".to_string()])),
+            Some(Value::List(vec!["ECU".to_string()])),
+        )];
+        apply_ignore_rules(&mut results, &rules("[[ignore]]\nfield = \"Receivers\"\nvalue = \"HIL_*\""));
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn value_rules_keep_results_without_matching_names() {
+        let mut results = vec![
+            ComparisonResult::object_exists(EntityKind::Node, "NEW", ChangeKind::Added),
+            ComparisonResult::exists(EntityKind::Message, "Msg", None, ChangeKind::Removed),
+        ];
+        let expected = results.clone();
+        apply_ignore_rules(&mut results, &rules("[[ignore]]\nmessage = \"*\"\nvalue = \"HIL_*\""));
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn rules_without_patterns_are_rejected() {
+        let error = toml::from_str::<IgnoreRules>("[[ignore]]\n").unwrap_err();
+        assert!(error.to_string().contains("would ignore every difference"), "{}", error);
+
+        assert!(toml::from_str::<IgnoreRules>("[[ignore]]\nfeild = \"Unit\"\n").is_err());
+        assert_eq!(rules("[[ignore]]\nvalue = \"HIL_*\"\n").len(), 1);
+    }
+
+    #[test]
+    fn rules_round_trip_through_toml() {
+        let source = IgnoreRules { defaults: false, ignore: default_ignore_rules() };
+        let text = toml::to_string(&source).unwrap();
+        assert_eq!(toml::from_str::<IgnoreRules>(&text).unwrap(), source);
+    }
+}
+
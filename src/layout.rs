@@ -0,0 +1,194 @@
+//! Payload bits occupied by signals.
+//!
+//! Bits are numbered by position in the payload: bit `n` is bit `n % 8` of
+//! byte `n / 8`, counting from the least significant bit of each byte.
+
+use std::collections::HashMap;
+use crate::model::{ChangeKind, ComparisonResult, EntityKind, Value};
+
+/// Payload bits occupied by a signal, ordered from its least to its most
+/// significant bit.
+///
+/// Intel signals start at their least significant bit and count up through
+/// the payload. Motorola signals start at their most significant bit and
+/// count down within a byte, continuing at the top of the next byte.
+pub fn signal_bits(signal: &rs_dbc::Signal) -> Vec<u64> {
+    let length = signal.signal_size() as usize;
+    let start = signal.start_bit();
+
+    if signal.byte_order() == "Intel" {
+        return (start..start + length as u64).collect();
+    }
+
+    let mut bits = Vec::with_capacity(length);
+    let mut bit = start;
+    for _ in 0..length {
+        bits.push(bit);
+        bit = if bit.is_multiple_of(8) { bit + 15 } else { bit - 1 };
+    }
+    bits.reverse();
+    bits
+}
+
+/// Groups ordered bits into runs of consecutive bits, as inclusive ranges.
+pub fn bit_runs(bits: &[u64]) -> Vec<(u64, u64)> {
+    let mut runs: Vec<(u64, u64)> = Vec::new();
+    for &bit in bits {
+        match runs.last_mut() {
+            Some((_, end)) if *end + 1 == bit => *end = bit,
+            _ => runs.push((bit, bit)),
+        }
+    }
+    runs
+}
+
+/// How a signal in one file's payload differs from the same signal in the
+/// other file. Ordered from least to most notable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SignalChange {
+    /// The signal occupies the same bits in both files.
+    Unchanged,
+    /// The signal has the same length but occupies different bits.
+    Moved,
+    /// The signal has a different length.
+    Resized,
+    /// The other file's message has no signal of this name.
+    Unmatched,
+}
+
+/// The signal occupying a payload bit and how it changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BitOwner<'a> {
+    pub signal: &'a str,
+    pub change: SignalChange,
+}
+
+/// Renamed signals of `message` among `results`, as pairs of their names in
+/// DBC1 and DBC2.
+pub fn signal_renames<'r>(results: &'r [ComparisonResult], message: &str) -> Vec<(&'r str, &'r str)> {
+    results
+        .iter()
+        .filter(|result| {
+            result.entity == EntityKind::Signal
+                && result.change == ChangeKind::Renamed
+                && result.message.as_deref() == Some(message)
+        })
+        .filter_map(|result| match (&result.dbc1, &result.dbc2) {
+            (Some(Value::Text(old_name)), Some(Value::Text(new_name))) => Some((old_name.as_str(), new_name.as_str())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Maps each of the first `bytes * 8` payload bits of `msg` to the signal
+/// occupying it, compared with the signals of `other`, the paired message
+/// in the other file.
+///
+/// Signals are paired by name, or through `renames`, which maps the names
+/// of renamed signals in `msg` to their names in `other`. Where signals
+/// overlap, as multiplexed signals do, the bit shows the most notable
+/// change so moved and resized signals are never hidden.
+pub fn payload_map<'a>(
+    msg: &'a rs_dbc::Message,
+    other: Option<&rs_dbc::Message>,
+    renames: &HashMap<&str, &str>,
+    bytes: usize,
+) -> Vec<Option<BitOwner<'a>>> {
+    let mut map: Vec<Option<BitOwner>> = vec![None; bytes * 8];
+
+    for signal in &msg.signals {
+        let bits = signal_bits(signal);
+        let name = renames.get(signal.name()).copied().unwrap_or(signal.name());
+        let counterpart = other.and_then(|other| other.signals.iter().find(|s| s.name() == name));
+        let change = match counterpart {
+            None => SignalChange::Unmatched,
+            Some(counterpart) if counterpart.signal_size() != signal.signal_size() => SignalChange::Resized,
+            Some(counterpart) if signal_bits(counterpart) != bits => SignalChange::Moved,
+            Some(_) => SignalChange::Unchanged,
+        };
+
+        for bit in bits {
+            let Some(cell) = map.get_mut(bit as usize) else { continue };
+            if cell.is_none_or(|owner| change > owner.change) {
+                *cell = Some(BitOwner { signal: signal.name(), change });
+            }
+        }
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_dbc;
+
+    const SIGNALS: &str = "\
+VERSION \"\"
+
+BU_: ECU PC
+
+BO_ 100 Layouts: 8 ECU
+ SG_ MotorolaWord : 23|16@0+ (1,0) [0|65535] \"\" PC
+ SG_ MotorolaBit : 6|1@0+ (1,0) [0|1] \"\" PC
+ SG_ IntelBit : 1|1@1+ (1,0) [0|1] \"\" PC
+ SG_ MotorolaByte : 7|8@0+ (1,0) [0|255] \"\" PC
+ SG_ IntelByte : 0|8@1+ (1,0) [0|255] \"\" PC
+
+";
+
+    fn bits_of(name: &str) -> Vec<u64> {
+        let file = parse_dbc(SIGNALS.as_bytes()).unwrap();
+        let signal = file.dbc.messages[0].signals.iter().find(|signal| signal.name() == name).unwrap();
+        signal_bits(signal)
+    }
+
+    #[test]
+    fn motorola_signals_continue_at_the_top_of_the_next_byte() {
+        let bits = bits_of("MotorolaWord");
+        assert_eq!(bit_runs(&bits), vec![(24, 31), (16, 23)]);
+    }
+
+    #[test]
+    fn single_bit_signals_occupy_their_start_bit() {
+        assert_eq!(bits_of("MotorolaBit"), vec![6]);
+        assert_eq!(bits_of("IntelBit"), vec![1]);
+    }
+
+    #[test]
+    fn byte_orders_can_occupy_the_same_bits() {
+        assert_eq!(bits_of("MotorolaByte"), bits_of("IntelByte"));
+        assert_eq!(bit_runs(&bits_of("IntelByte")), vec![(0, 7)]);
+    }
+
+    #[test]
+    fn bit_runs_split_at_gaps() {
+        assert_eq!(bit_runs(&[]), vec![]);
+        assert_eq!(bit_runs(&[3]), vec![(3, 3)]);
+        assert_eq!(bit_runs(&[0, 1, 2, 5, 6, 9]), vec![(0, 2), (5, 6), (9, 9)]);
+    }
+
+    #[test]
+    fn renamed_signals_are_paired_through_the_renames() {
+        let file = |signal: &str| {
+            let text = format!("VERSION \"\"\n\nBU_: ECU PC\n\nBO_ 100 Msg: 8 ECU\n SG_ {} \"\" PC\n\n", signal);
+            parse_dbc(text.as_bytes()).unwrap()
+        };
+        let dbc1 = file("EngineSpeed : 0|16@1+ (1,0) [0|65535]");
+        let dbc2 = file("EngineSpeedRaw : 0|12@1+ (1,0) [0|4095]");
+        let results = crate::compare_dbc_files(&dbc1, &dbc2).unwrap();
+
+        let renames = signal_renames(&results, "Msg");
+        assert_eq!(renames, vec![("EngineSpeed", "EngineSpeedRaw")]);
+
+        let (msg1, msg2) = (&dbc1.dbc.messages[0], &dbc2.dbc.messages[0]);
+        let renames1: HashMap<&str, &str> = renames.iter().copied().collect();
+        let renames2: HashMap<&str, &str> = renames.iter().map(|&(name1, name2)| (name2, name1)).collect();
+        let map1 = payload_map(msg1, Some(msg2), &renames1, 8);
+        let map2 = payload_map(msg2, Some(msg1), &renames2, 8);
+
+        assert_eq!(map1[0], Some(BitOwner { signal: "EngineSpeed", change: SignalChange::Resized }));
+        assert_eq!(map2[0], Some(BitOwner { signal: "EngineSpeedRaw", change: SignalChange::Resized }));
+        assert_eq!(payload_map(msg1, Some(msg2), &HashMap::new(), 8)[0].unwrap().change, SignalChange::Unmatched);
+    }
+}
+
@@ -0,0 +1,71 @@
+//! Comparison engine behind the TwinCAN DBC diff tool.
+//!
+//! Load two DBC files with [`load_dbc`], compare them with
+//! [`compare_dbc_files`] and either inspect the returned
+//! [`ComparisonResult`]s or write them to a CSV file with
+//! [`export_comparison_to_csv`]. Results are typed; [`report::render`] turns
+//! them into the strings shown in the results table. [`lint_dbc_file`] checks
+//! a single file and reports its problems as results of the same type, and
+//! [`compare_three_way`] compares two versions against their common ancestor.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let dbc1 = twincan::load_dbc("examples/1.dbc")?;
+//! let dbc2 = twincan::load_dbc("examples/2.dbc")?;
+//!
+//! for result in twincan::compare_dbc_files(&dbc1, &dbc2)? {
+//!     if result.field == twincan::Field::Length {
+//!         println!("{:?} {:?}: {:?} -> {:?}", result.message, result.signal, result.dbc1, result.dbc2);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+use rs_dbc::Dbc;
+
+pub mod cli;
+pub mod compare;
+pub mod export;
+pub mod ignore;
+pub mod layout;
+pub mod lint;
+pub mod model;
+pub mod network;
+pub mod project;
+pub mod report;
+pub mod severity;
+pub mod similarity;
+pub mod three_way;
+pub mod tolerance;
+
+pub use compare::{compare_dbc_files, compare_dbc_files_with, find_message_pair, CompareOptions, MatchMode};
+pub use model::{ChangeKind, ComparisonResult, EntityKind, Field, Value};
+pub use network::{DbcFile, Network};
+pub use severity::{classify, Severity};
+pub use similarity::{TextMatch, TextMatching};
+pub use tolerance::{Tolerance, Tolerances};
+pub use export::{export_comparison_to_csv, write_results_csv};
+pub use ignore::{default_ignore_rules, load_ignore_rules, IgnoreRule, IgnoreRules, Pattern};
+pub use project::{Project, Review, ReviewStatus};
+pub use lint::lint_dbc_file;
+pub use three_way::{compare_three_way, ThreeWayChange, ThreeWayResult};
+pub use rs_dbc;
+
+/// Reads and parses the DBC file at `path`.
+pub fn load_dbc(path: &str) -> Result<DbcFile, Box<dyn Error>> {
+    let mut file = File::open(path)?;
+    let mut buffer = vec![];
+    file.read_to_end(&mut buffer)?;
+    Ok(parse_dbc(&buffer).map_err(|e| format!("Failed to parse DBC file '{}': {}", path, e))?)
+}
+
+/// Parses DBC file content.
+pub fn parse_dbc(buffer: &[u8]) -> Result<DbcFile, String> {
+    let dbc = Dbc::from_slice_lossy(buffer).map_err(|e| format!("{:?}", e))?;
+    let network = Network::parse(&String::from_utf8_lossy(buffer));
+    Ok(DbcFile { dbc, network })
+}
@@ -0,0 +1,277 @@
+//! Consistency checks on a single DBC file.
+
+use std::collections::HashMap;
+use rs_dbc::Message;
+use crate::compare::{file_message_id, physical_range, range_fit, signal_ref};
+use crate::ignore::placeholder_node;
+use crate::layout::{bit_runs, signal_bits};
+use crate::model::{ComparisonResult, EntityKind, Field, RangeFit, Value};
+use crate::network::{DbcFile, Network};
+
+/// Checks one DBC file for problems.
+///
+/// Reports messages sharing a CAN ID, signals overlapping other signals that
+/// can be present at the same time, signals extending past the message's
+/// DLC, declared `[min|max]` ranges the raw bits cannot represent and
+/// receivers missing from `BU_`. Problems are returned as results with
+/// [`ChangeKind::Problem`](crate::ChangeKind::Problem), in file order.
+///
+/// rs_dbc merges the signals of messages sharing an ID, so the signals of
+/// those messages are not checked; only the shared ID is reported.
+pub fn lint_dbc_file(file: &DbcFile) -> Vec<ComparisonResult> {
+    let mut results = Vec::new();
+    let mut ids: HashMap<u32, &str> = HashMap::new();
+    let mut counts: HashMap<u32, usize> = HashMap::new();
+    for (id, _) in &file.network.messages {
+        *counts.entry(*id).or_default() += 1;
+    }
+
+    // rs_dbc keeps one message per ID, so walk the BO_ lines in file order
+    for (id, name) in &file.network.messages {
+        // Check the ID is not used by an earlier message
+        if let Some(first) = ids.get(id) {
+            results.push(ComparisonResult::problem(
+                EntityKind::Message,
+                name,
+                None,
+                Field::DuplicateId,
+                Some(Value::Id(*id)),
+                Some(Value::Text(first.to_string())),
+            ));
+            continue;
+        }
+        ids.insert(*id, name);
+        if counts[id] > 1 {
+            continue;
+        }
+
+        if let Some(msg) = file.dbc.messages.iter().find(|msg| file_message_id(msg) == *id) {
+            lint_signals_for_results(&mut results, file, name, msg);
+        }
+    }
+
+    results
+}
+
+fn lint_signals_for_results(results: &mut Vec<ComparisonResult>, file: &DbcFile, msg_name: &str, msg: &Message) {
+    let network = &file.network;
+    let payload_bits = msg.message_size() * 8;
+    let bits: Vec<Vec<u64>> = msg.signals.iter().map(signal_bits).collect();
+    let placeholder = placeholder_node();
+
+    for (index, signal) in msg.signals.iter().enumerate() {
+        let signal_name = signal.name();
+        let mut push = |field: Field, value: Option<Value>, reference: Option<Value>| {
+            results.push(ComparisonResult::problem(
+                EntityKind::Signal,
+                msg_name,
+                Some(signal_name),
+                field,
+                value,
+                reference,
+            ));
+        };
+
+        // Check for overlaps with later signals, so each pair is reported once
+        for (other, other_bits) in msg.signals.iter().zip(&bits).skip(index + 1) {
+            if !coexist(network, msg, signal, other) {
+                continue;
+            }
+            let shared: Vec<u64> = bits[index].iter().copied().filter(|bit| other_bits.contains(bit)).collect();
+            if !shared.is_empty() {
+                push(
+                    Field::Overlap,
+                    Some(Value::IntegerRanges(bit_runs(&shared))),
+                    Some(Value::Text(other.name().to_string())),
+                );
+            }
+        }
+
+        // Check the signal fits in the payload
+        let beyond: Vec<u64> = bits[index].iter().copied().filter(|&bit| bit >= payload_bits).collect();
+        if !beyond.is_empty() {
+            let payload = (payload_bits > 0).then(|| Value::IntegerRanges(vec![(0, payload_bits - 1)]));
+            push(Field::BeyondPayload, Some(Value::IntegerRanges(bit_runs(&beyond))), payload);
+        }
+
+        // Check the declared range fits the raw bits
+        let physical = physical_range(signal, network.value_type_of(&signal_ref(msg, signal)));
+        if let (RangeFit::Exceeds, Some((min, max))) = (range_fit(signal, physical), physical) {
+            push(Field::RangeFit, Some(Value::Range(signal.min(), signal.max())), Some(Value::Range(min, max)));
+        }
+
+        // Check receivers are declared nodes
+        for receiver in signal.receivers() {
+            if !placeholder.matches(receiver) && !network.nodes.contains(receiver) {
+                push(Field::UndeclaredReceiver, Some(Value::Text(receiver.clone())), None);
+            }
+        }
+    }
+}
+
+/// Returns `true` when two signals of a message can be present in the same frame.
+///
+/// Signals that are not multiplexed are always present. Multiplexed signals
+/// selected by the same multiplexor coexist when their multiplexor values,
+/// or `SG_MUL_VAL_` ranges, overlap. Signals selected by different
+/// multiplexors are assumed not to coexist.
+fn coexist(network: &Network, msg: &Message, sig1: &rs_dbc::Signal, sig2: &rs_dbc::Signal) -> bool {
+    match (mux_pages(network, msg, sig1), mux_pages(network, msg, sig2)) {
+        (Some((mux1, pages1)), Some((mux2, pages2))) => {
+            mux1 == mux2
+                && pages1.iter().any(|(from1, to1)| pages2.iter().any(|(from2, to2)| from1 <= to2 && from2 <= to1))
+        },
+        _ => true,
+    }
+}
+
+/// Multiplexor and multiplexor value ranges selecting a signal, or `None`
+/// for signals that are always present.
+fn mux_pages<'a>(network: &'a Network, msg: &Message, signal: &rs_dbc::Signal) -> Option<(&'a str, Vec<(u64, u64)>)> {
+    let target = signal_ref(msg, signal);
+    let value = network.multiplexing_of(&target).multiplexor_value?;
+    let multiplexor = network.multiplexor_of(file_message_id(msg), signal.name()).unwrap_or_default();
+    let pages = match network.extended_multiplexing.get(&target) {
+        Some(extended) => extended.ranges.clone(),
+        None => vec![(value, value)],
+    };
+    Some((multiplexor, pages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_dbc;
+
+    const SHARED_ID: &str = "\
+VERSION \"\"
+
+BU_: ECU1
+
+BO_ 100 First: 8 ECU1
+ SG_ A : 0|8@1+ (1,0) [0|255] \"\" ECU1
+
+BO_ 100 Second: 8 ECU1
+ SG_ B : 0|8@1+ (1,0) [0|255] \"\" ECU1
+
+";
+
+    #[test]
+    fn messages_sharing_an_id_report_only_the_id() {
+        let file = parse_dbc(SHARED_ID.as_bytes()).unwrap();
+        let results = lint_dbc_file(&file);
+
+        assert_eq!(results.len(), 1, "{:?}", results);
+        assert_eq!(results[0].field, Field::DuplicateId);
+        assert_eq!(results[0].message.as_deref(), Some("Second"));
+        assert_eq!(results[0].dbc2, Some(Value::Text("First".to_string())));
+    }
+
+    /// Problems found in a file with the nodes `ECU PC` and the given `BO_` blocks.
+    fn lint(messages: &str) -> Vec<ComparisonResult> {
+        let text = format!("VERSION \"\"\n\nBU_: ECU PC\n\n{}\n", messages);
+        lint_dbc_file(&parse_dbc(text.as_bytes()).unwrap())
+    }
+
+    /// Signal, field and values of each problem, for comparing with `assert_eq!`.
+    fn problems(results: &[ComparisonResult]) -> Vec<(&str, Field, Option<Value>, Option<Value>)> {
+        results
+            .iter()
+            .map(|result| {
+                let signal = result.signal.as_deref().unwrap_or("");
+                (signal, result.field.clone(), result.dbc1.clone(), result.dbc2.clone())
+            })
+            .collect()
+    }
+
+    fn bits(runs: &[(u64, u64)]) -> Option<Value> {
+        Some(Value::IntegerRanges(runs.to_vec()))
+    }
+
+    fn text(text: &str) -> Option<Value> {
+        Some(Value::Text(text.to_string()))
+    }
+
+    #[test]
+    fn overlapping_signals_are_reported_once() {
+        let results = lint("\
+BO_ 100 Plain: 8 ECU
+ SG_ A : 0|8@1+ (1,0) [0|255] \"\" PC
+ SG_ B : 4|8@1+ (1,0) [0|255] \"\" PC
+ SG_ C : 16|8@1+ (1,0) [0|255] \"\" PC
+");
+        assert_eq!(problems(&results), vec![("A", Field::Overlap, bits(&[(4, 7)]), text("B"))]);
+    }
+
+    #[test]
+    fn multiplexed_signals_overlap_only_on_the_same_page() {
+        let results = lint("\
+BO_ 100 Muxed: 8 ECU
+ SG_ Mux M : 0|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Page1 m1 : 8|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Page2 m2 : 8|8@1+ (1,0) [0|255] \"\" PC
+ SG_ AlsoPage1 m1 : 12|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Always : 4|8@1+ (1,0) [0|255] \"\" PC
+");
+        assert_eq!(problems(&results), vec![
+            ("Mux", Field::Overlap, bits(&[(4, 7)]), text("Always")),
+            ("Page1", Field::Overlap, bits(&[(12, 15)]), text("AlsoPage1")),
+            ("Page1", Field::Overlap, bits(&[(8, 11)]), text("Always")),
+            ("Page2", Field::Overlap, bits(&[(8, 11)]), text("Always")),
+        ]);
+    }
+
+    #[test]
+    fn extended_multiplexing_ranges_select_the_pages() {
+        let results = lint("\
+BO_ 100 Muxed: 8 ECU
+ SG_ Mux M : 0|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Low m1 : 8|8@1+ (1,0) [0|255] \"\" PC
+ SG_ High m4 : 8|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Wide m2 : 8|8@1+ (1,0) [0|255] \"\" PC
+
+SG_MUL_VAL_ 100 Low Mux 1-2;
+SG_MUL_VAL_ 100 High Mux 4-5;
+SG_MUL_VAL_ 100 Wide Mux 2-3;
+");
+        assert_eq!(problems(&results), vec![("Low", Field::Overlap, bits(&[(8, 15)]), text("Wide"))]);
+    }
+
+    #[test]
+    fn signals_past_the_dlc_are_reported() {
+        let results = lint("\
+BO_ 100 Short: 2 ECU
+ SG_ Inside : 0|16@1+ (1,0) [0|65535] \"\" PC
+ SG_ Outside : 12|8@1+ (1,0) [0|255] \"\" PC
+");
+        let beyond: Vec<_> =
+            problems(&results).into_iter().filter(|problem| problem.1 == Field::BeyondPayload).collect();
+        assert_eq!(beyond, vec![("Outside", Field::BeyondPayload, bits(&[(16, 19)]), bits(&[(0, 15)]))]);
+    }
+
+    #[test]
+    fn declared_ranges_past_the_raw_bits_are_reported() {
+        let results = lint("\
+BO_ 100 Ranges: 8 ECU
+ SG_ Fits : 0|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Exceeds : 8|8@1+ (1,0) [0|300] \"\" PC
+");
+        assert_eq!(problems(&results), vec![(
+            "Exceeds",
+            Field::RangeFit,
+            Some(Value::Range(0.0, 300.0)),
+            Some(Value::Range(0.0, 255.0)),
+        )]);
+    }
+
+    #[test]
+    fn receivers_missing_from_the_node_list_are_reported() {
+        let results = lint("\
+BO_ 100 Received: 8 ECU
+ SG_ Known : 0|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Unknown : 8|8@1+ (1,0) [0|255] \"\" Tester,Vector__XXX
+");
+        assert_eq!(problems(&results), vec![("Unknown", Field::UndeclaredReceiver, text("Tester"), None)]);
+    }
+}
+
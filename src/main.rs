@@ -1,48 +1,56 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::error::Error;
-use std::fs::File;
-use std::io::{Read, Write};
-use std::collections::{HashMap, HashSet};
-use rs_dbc::Dbc;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use rfd::FileDialog;
-use slint::{ComponentHandle, VecModel, ModelRc, Model};
+use slint::{ComponentHandle, Model, VecModel, ModelRc};
+use twincan::report::render;
+use twincan::severity::filter_by_severity;
+use twincan::similarity::parse_threshold;
+use twincan::layout::{payload_map, signal_renames, BitOwner, SignalChange};
+use twincan::rs_dbc::Message;
+use twincan::{
+    compare_dbc_files_with, export_comparison_to_csv, find_message_pair, lint_dbc_file, load_dbc,
+    ComparisonResult, DbcFile, IgnoreRules, MatchMode, Project, Review, ReviewStatus, Severity, TextMatch,
+    TextMatching,
+};
 
 slint::include_modules!();
 
-#[derive(Clone, Debug)]
-struct ComparisonResult {
-    result_type: String,
-    message: String,
-    signal: String,
-    field: String,
-    dbc1: String,
-    dbc2: String,
-}
-
 impl From<ComparisonResult> for ComparisonResultItem {
     fn from(result: ComparisonResult) -> Self {
+        let row = render(&result);
         ComparisonResultItem {
-            r#type: result.result_type.into(),
-            message: result.message.into(),
-            signal: result.signal.into(),
-            field: result.field.into(),
-            dbc1: result.dbc1.into(),
-            dbc2: result.dbc2.into(),
+            r#type: row.result_type.into(),
+            severity: row.severity.into(),
+            message: row.message.into(),
+            signal: row.signal.into(),
+            field: row.field.into(),
+            dbc1: row.dbc1.into(),
+            dbc2: row.dbc2.into(),
+            details: row.details.into(),
+            review_status: 0,
+            review: "".into(),
         }
     }
 }
 
-fn load_dbc(path: &str) -> Result<Dbc, Box<dyn Error>> {
-    let mut file = File::open(path)?;
-    let mut buffer = vec![];
-    file.read_to_end(&mut buffer)?;
-    Dbc::from_slice_lossy(&buffer).map_err(|e| format!("Failed to parse DBC file '{}': {:?}", path, e).into())
-}
-
 fn main() -> Result<(), slint::PlatformError> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = twincan::cli::run(&args) {
+        std::process::exit(code);
+    }
+
     let ui = MainWindow::new()?;
 
+    // Results of the last comparison; the table and exports show them filtered by severity
+    let current_results: Rc<RefCell<Vec<ComparisonResult>>> = Rc::new(RefCell::new(Vec::new()));
+    // Files of the last comparison and how their messages were paired, for the payload layout
+    let current_files: Rc<RefCell<Option<(DbcFile, DbcFile, MatchMode)>>> = Rc::new(RefCell::new(None));
+    // Project of the comparison, holding the reviews of its rows
+    let current_project: Rc<RefCell<Project>> = Rc::new(RefCell::new(Project::default()));
+
     let ui_weak = ui.as_weak();
     ui.on_select_dbc1_file(move || {
         let ui = ui_weak.unwrap();
@@ -54,6 +62,17 @@ fn main() -> Result<(), slint::PlatformError> {
             }
     });
 
+    let ui_weak = ui.as_weak();
+    ui.on_select_rules_file(move || {
+        let ui = ui_weak.unwrap();
+        if let Some(path) = FileDialog::new()
+            .add_filter("Rule files", &["toml", "twincan"])
+            .pick_file()
+            {
+                ui.set_rules_path(path.to_string_lossy().to_string().into());
+            }
+    });
+
     let ui_weak = ui.as_weak();
     ui.on_select_dbc2_file(move || {
         let ui = ui_weak.unwrap();
@@ -66,27 +85,39 @@ fn main() -> Result<(), slint::PlatformError> {
     });
 
     let ui_weak = ui.as_weak();
+    let compared_results = current_results.clone();
+    let compared_files = current_files.clone();
+    let compared_project = current_project.clone();
     ui.on_compare_files(move || {
         let ui = ui_weak.unwrap();
-        let dbc1_path = ui.get_dbc1_path().to_string();
-        let dbc2_path = ui.get_dbc2_path().to_string();
+        let project = match project_from_ui(&ui) {
+            Ok(project) => project,
+            Err(e) => {
+                ui.set_status(e.into());
+                return;
+            }
+        };
+        let options = project.compare_options();
 
         ui.set_status("Comparing files...".into());
 
-        match (load_dbc(&dbc1_path), load_dbc(&dbc2_path)) {
+        match (load_dbc(&project.dbc1), load_dbc(&project.dbc2)) {
             (Ok(dbc1), Ok(dbc2)) => {
-                match compare_dbc_files(&dbc1, &dbc2) {
+                match compare_dbc_files_with(&dbc1, &dbc2, &options) {
                     Ok(results) => {
-                        let slint_results: Vec<ComparisonResultItem> = results
-                        .into_iter()
-                        .map(Into::into)
-                        .collect();
-                        let model = ModelRc::new(VecModel::from(slint_results)); // ✅ FIXED
-                        ui.set_comparison_results(model);
-                        ui.set_status(format!(
-                            "Comparison complete. Found {} differences.",
-                            ui.get_comparison_results().row_count() // ✅ FIXED
-                        ).into());
+                        // Reviews belong to the file pair they were made on
+                        let same_pair = {
+                            let current = compared_project.borrow();
+                            current.dbc1 == project.dbc1 && current.dbc2 == project.dbc2
+                        };
+                        if !same_pair {
+                            *compared_project.borrow_mut() = project;
+                        }
+
+                        show_results(&ui, &results, Some(&compared_project.borrow()));
+                        clear_layout(&ui);
+                        *compared_results.borrow_mut() = results;
+                        *compared_files.borrow_mut() = Some((dbc1, dbc2, options.match_mode));
                     }
                     Err(e) => {
                         ui.set_status(format!("Error during comparison: {}", e).into());
@@ -99,6 +130,148 @@ fn main() -> Result<(), slint::PlatformError> {
     });
 
     let ui_weak = ui.as_weak();
+    let linted_results = current_results.clone();
+    let linted_files = current_files.clone();
+    ui.on_lint_file(move |file| {
+        let ui = ui_weak.unwrap();
+        let path = if file == 2 { ui.get_dbc2_path() } else { ui.get_dbc1_path() }.to_string();
+
+        ui.set_status(format!("Linting DBC{}...", file).into());
+
+        match load_dbc(&path) {
+            Ok(dbc) => {
+                let results = lint_dbc_file(&dbc);
+                show_results(&ui, &results, None);
+                clear_layout(&ui);
+                *linted_results.borrow_mut() = results;
+                *linted_files.borrow_mut() = None;
+            }
+            Err(e) => ui.set_status(format!("Error loading DBC{}: {}", file, e).into()),
+        }
+    });
+
+    let ui_weak = ui.as_weak();
+    let refreshed_results = current_results.clone();
+    let refreshed_files = current_files.clone();
+    let refreshed_project = current_project.clone();
+    ui.on_refresh_results(move || {
+        let ui = ui_weak.unwrap();
+        let results = refreshed_results.borrow();
+        if !results.is_empty() {
+            // Only comparisons keep their files
+            let project = refreshed_project.borrow();
+            show_results(&ui, &results, refreshed_files.borrow().is_some().then_some(&*project));
+        }
+    });
+
+    let ui_weak = ui.as_weak();
+    let selected_results = current_results.clone();
+    let selected_files = current_files.clone();
+    let selected_project = current_project.clone();
+    ui.on_result_selected(move |index| {
+        let ui = ui_weak.unwrap();
+        let shown = filter_by_severity(&selected_results.borrow(), severity_filter(&ui), ui.get_sort_by_severity());
+        let files = selected_files.borrow();
+        let result = shown.get(index as usize);
+        let message = result.and_then(|result| result.message.as_deref());
+
+        let project = selected_project.borrow();
+        let review = result.and_then(|result| project.review_of(result));
+        ui.set_review_status(review.map_or(0, |review| review_status_index(review.status)));
+        ui.set_review_note(review.map_or("", |review| review.note.as_str()).into());
+
+        match (files.as_ref(), message) {
+            (Some((dbc1, dbc2, match_mode)), Some(message)) => {
+                let (msg1, msg2) = find_message_pair(dbc1, dbc2, message, *match_mode);
+                let results = selected_results.borrow();
+                show_layout(&ui, message, msg1, msg2, &signal_renames(&results, message));
+            }
+            _ => clear_layout(&ui),
+        }
+    });
+
+    let ui_weak = ui.as_weak();
+    let reviewed_results = current_results.clone();
+    let reviewed_project = current_project.clone();
+    ui.on_review_changed(move || {
+        let ui = ui_weak.unwrap();
+        let index = ui.get_selected_row();
+        let shown = filter_by_severity(&reviewed_results.borrow(), severity_filter(&ui), ui.get_sort_by_severity());
+        let Some(result) = shown.get(index as usize) else {
+            return;
+        };
+        let status = match ui.get_review_status() {
+            1 => ReviewStatus::Accepted,
+            2 => ReviewStatus::Rejected,
+            _ => ReviewStatus::Open,
+        };
+
+        let mut project = reviewed_project.borrow_mut();
+        project.set_review(result, status, &ui.get_review_note());
+        let items = ui.get_comparison_results();
+        if let Some(mut item) = items.row_data(index as usize) {
+            item.review_status = ui.get_review_status();
+            item.review = review_text(project.review_of(result)).into();
+            items.set_row_data(index as usize, item);
+        }
+    });
+
+    let ui_weak = ui.as_weak();
+    let opened_project = current_project.clone();
+    ui.on_open_project(move || {
+        let ui = ui_weak.unwrap();
+        if let Some(path) = FileDialog::new()
+            .add_filter("TwinCAN projects", &["twincan"])
+            .pick_file()
+            {
+                let path = path.to_string_lossy().to_string();
+                match Project::load(&path) {
+                    Ok(project) => {
+                        show_project(&ui, &project, &path);
+                        *opened_project.borrow_mut() = project;
+                        if !ui.get_dbc1_path().is_empty() && !ui.get_dbc2_path().is_empty() {
+                            ui.invoke_compare_files();
+                        } else {
+                            ui.set_status(format!("Project opened: {}", path).into());
+                        }
+                    }
+                    Err(e) => {
+                        ui.set_status(format!("Error opening project: {}", e).into());
+                    }
+                }
+            }
+    });
+
+    let ui_weak = ui.as_weak();
+    let saved_project = current_project.clone();
+    ui.on_save_project(move || {
+        let ui = ui_weak.unwrap();
+        let project = match project_from_ui(&ui) {
+            Ok(project) => Project { reviews: saved_project.borrow().reviews.clone(), ..project },
+            Err(e) => {
+                ui.set_status(e.into());
+                return;
+            }
+        };
+        if let Some(path) = FileDialog::new()
+            .add_filter("TwinCAN projects", &["twincan"])
+            .set_file_name("comparison.twincan")
+            .save_file()
+            {
+                match project.save(&path.to_string_lossy()) {
+                    Ok(_) => {
+                        ui.set_status(format!("Project saved to: {}", path.to_string_lossy()).into());
+                        *saved_project.borrow_mut() = project;
+                    }
+                    Err(e) => {
+                        ui.set_status(format!("Error saving project: {}", e).into());
+                    }
+                }
+            }
+    });
+
+    let ui_weak = ui.as_weak();
+    let exported_results = current_results.clone();
     ui.on_export_csv(move || {
         let ui = ui_weak.unwrap();
         if let Some(path) = FileDialog::new()
@@ -106,22 +279,14 @@ fn main() -> Result<(), slint::PlatformError> {
             .set_file_name("dbc_comparison.csv")
             .save_file()
             {
-                let dbc1_path = ui.get_dbc1_path().to_string();
-                let dbc2_path = ui.get_dbc2_path().to_string();
-
-                match (load_dbc(&dbc1_path), load_dbc(&dbc2_path)) {
-                    (Ok(dbc1), Ok(dbc2)) => {
-                        match export_comparison_to_csv(&dbc1, &dbc2, &path.to_string_lossy()) {
-                            Ok(_) => {
-                                ui.set_status(format!("CSV exported to: {}", path.to_string_lossy()).into());
-                            }
-                            Err(e) => {
-                                ui.set_status(format!("Error exporting CSV: {}", e).into());
-                            }
-                        }
+                let shown = filter_by_severity(&exported_results.borrow(), severity_filter(&ui), ui.get_sort_by_severity());
+                match export_comparison_to_csv(&shown, &path.to_string_lossy()) {
+                    Ok(_) => {
+                        ui.set_status(format!("CSV exported to: {}", path.to_string_lossy()).into());
+                    }
+                    Err(e) => {
+                        ui.set_status(format!("Error exporting CSV: {}", e).into());
                     }
-                    (Err(e), _) => ui.set_status(format!("Error loading DBC1: {}", e).into()),
-                     (_, Err(e)) => ui.set_status(format!("Error loading DBC2: {}", e).into()),
                 }
             }
     });
@@ -129,808 +294,180 @@ fn main() -> Result<(), slint::PlatformError> {
     ui.run()
 }
 
-fn compare_dbc_files(dbc1: &Dbc, dbc2: &Dbc) -> Result<Vec<ComparisonResult>, Box<dyn Error>> {
-    
-    let mut results = Vec::new();
-    
-    // Create maps for quick lookup by message name
-    let mut dbc1_messages: HashMap<String, &rs_dbc::Message> = HashMap::new();
-    let mut dbc2_messages: HashMap<String, &rs_dbc::Message> = HashMap::new();
-    
-    for msg in &dbc1.messages {
-        dbc1_messages.insert(msg.message_name().to_string(), msg);
-    }
-    
-    for msg in &dbc2.messages {
-        dbc2_messages.insert(msg.message_name().to_string(), msg);
-    }
-    
-    // Get all unique message names
-    let mut all_message_names: HashSet<String> = HashSet::new();
-    all_message_names.extend(dbc1_messages.keys().cloned());
-    all_message_names.extend(dbc2_messages.keys().cloned());
-    
-    // Sort message names alphabetically
-    let mut sorted_message_names: Vec<String> = all_message_names.into_iter().collect();
-    sorted_message_names.sort();
-    
-    for msg_name in &sorted_message_names {
-        let msg1 = dbc1_messages.get(msg_name);
-        let msg2 = dbc2_messages.get(msg_name);
-        
-        match (msg1, msg2) {
-            (Some(m1), Some(m2)) => {
-                // Both DBCs have this message - compare properties
-                compare_message_properties_for_results(&mut results, m1, m2);
-                compare_signals_for_results(&mut results, m1, m2);
-            },
-            (Some(m1), None) => {
-                // Only DBC1 has this message
-                results.push(ComparisonResult {
-                    result_type: "Message".to_string(),
-                    message: m1.message_name().to_string(),
-                    signal: "".to_string(),
-                    field: "Exists".to_string(),
-                    dbc1: "Yes".to_string(),
-                    dbc2: "No".to_string(),
-                });
-            },
-            (None, Some(m2)) => {
-                // Only DBC2 has this message
-                results.push(ComparisonResult {
-                    result_type: "Message".to_string(),
-                    message: m2.message_name().to_string(),
-                    signal: "".to_string(),
-                    field: "Exists".to_string(),
-                    dbc1: "No".to_string(),
-                    dbc2: "Yes".to_string(),
-                });
-            },
-            (None, None) => unreachable!(),
-        }
-    }
-    
-    Ok(results)
-}
-
-fn compare_message_properties_for_results(
-    results: &mut Vec<ComparisonResult>,
-    msg1: &rs_dbc::Message, 
-    msg2: &rs_dbc::Message
-) {
-    let msg_name = msg1.message_name();
-    
-    // Compare message size
-    if msg1.message_size() != msg2.message_size() {
-        results.push(ComparisonResult {
-            result_type: "Message".to_string(),
-            message: msg_name.to_string(),
-            signal: "".to_string(),
-            field: "DLC".to_string(),
-            dbc1: msg1.message_size().to_string(),
-            dbc2: msg2.message_size().to_string(),
-        });
-    }
-    
-    // Compare cycle time
-    if msg1.cycle_time() != msg2.cycle_time() {
-        results.push(ComparisonResult {
-            result_type: "Message".to_string(),
-            message: msg_name.to_string(),
-            signal: "".to_string(),
-            field: "Cycle Time".to_string(),
-            dbc1: msg1.cycle_time().to_string(),
-            dbc2: msg2.cycle_time().to_string(),
-        });
-    }
-    
-    // Compare transmitter
-    if msg1.transmitter() != msg2.transmitter() {
-        results.push(ComparisonResult {
-            result_type: "Message".to_string(),
-            message: msg_name.to_string(),
-            signal: "".to_string(),
-            field: "Transmitter".to_string(),
-            dbc1: msg1.transmitter().to_string(),
-            dbc2: msg2.transmitter().to_string(),
-        });
-    }
-    
-    // Compare message ID
-    let (id1, kind1) = msg1.message_id();
-    let (id2, kind2) = msg2.message_id();
-    if id1 != id2 {
-        results.push(ComparisonResult {
-            result_type: "Message".to_string(),
-            message: msg_name.to_string(),
-            signal: "".to_string(),
-            field: "Message ID".to_string(),
-            dbc1: format!("0x{:X}", id1),
-            dbc2: format!("0x{:X}", id2),
-        });
-    }
-    
-    // Compare message ID kind
-    if kind1 != kind2 {
-        results.push(ComparisonResult {
-            result_type: "Message".to_string(),
-            message: msg_name.to_string(),
-            signal: "".to_string(),
-            field: "ID Format".to_string(),
-            dbc1: format!("{:?}", kind1),
-            dbc2: format!("{:?}", kind2),
-        });
-    }
-}
-
-fn compare_signals_for_results(
-    results: &mut Vec<ComparisonResult>,
-    msg1: &rs_dbc::Message, 
-    msg2: &rs_dbc::Message
-) {
-    let msg_name = msg1.message_name();
-    
-    // Create maps for signal lookup
-    let mut signals1: HashMap<&str, &rs_dbc::Signal> = HashMap::new();
-    let mut signals2: HashMap<&str, &rs_dbc::Signal> = HashMap::new();
-    
-    for signal in &msg1.signals {
-        signals1.insert(signal.name(), signal);
-    }
-    
-    for signal in &msg2.signals {
-        signals2.insert(signal.name(), signal);
-    }
-    
-    // Get all unique signal names
-    let mut all_signal_names: HashSet<&str> = HashSet::new();
-    all_signal_names.extend(signals1.keys());
-    all_signal_names.extend(signals2.keys());
-    
-    // Sort signal names alphabetically
-    let mut sorted_signal_names: Vec<&str> = all_signal_names.into_iter().collect();
-    sorted_signal_names.sort();
-    
-    for &signal_name in &sorted_signal_names {
-        let sig1 = signals1.get(signal_name);
-        let sig2 = signals2.get(signal_name);
-        
-        match (sig1, sig2) {
-            (Some(s1), Some(s2)) => {
-                // Both DBCs have this signal - compare properties
-                compare_signal_properties_for_results(results, msg_name, s1, s2);
-            },
-            (Some(s1), None) => {
-                // Only DBC1 has this signal
-                results.push(ComparisonResult {
-                    result_type: "Signal".to_string(),
-                    message: msg_name.to_string(),
-                    signal: s1.name().to_string(),
-                    field: "Exists".to_string(),
-                    dbc1: "Yes".to_string(),
-                    dbc2: "No".to_string(),
-                });
-            },
-            (None, Some(s2)) => {
-                // Only DBC2 has this signal
-                results.push(ComparisonResult {
-                    result_type: "Signal".to_string(),
-                    message: msg_name.to_string(),
-                    signal: s2.name().to_string(),
-                    field: "Exists".to_string(),
-                    dbc1: "No".to_string(),
-                    dbc2: "Yes".to_string(),
-                });
-            },
-            (None, None) => unreachable!(),
-        }
+/// Minimum severity selected in the "Show" filter.
+fn severity_filter(ui: &MainWindow) -> Severity {
+    match ui.get_severity_filter() {
+        1 => Severity::PotentiallyBreaking,
+        2 => Severity::Breaking,
+        _ => Severity::Cosmetic,
     }
 }
 
-fn compare_signal_properties_for_results(
-    results: &mut Vec<ComparisonResult>,
-    msg_name: &str,
-    sig1: &rs_dbc::Signal,
-    sig2: &rs_dbc::Signal
-) {
-    let signal_name = sig1.name();
-    
-    // Check both raw and Vector start bits
-    let raw_bit1 = sig1.start_bit();
-    let raw_bit2 = sig2.start_bit();
-    let vector_bit1 = sig1.vector_start_bit();
-    let vector_bit2 = sig2.vector_start_bit();
-    
-    let raw_different = raw_bit1 != raw_bit2;
-    let vector_different = vector_bit1 != vector_bit2;
-    
-    // If Vector bits are different, show Vector output only
-    if vector_different {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Start Bit (Vector)".to_string(),
-            dbc1: vector_bit1.to_string(),
-            dbc2: vector_bit2.to_string(),
-        });
-    }
-    // If Vector bits are same but raw bits are different, show raw output only
-    if raw_different {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Start Bit (Raw)".to_string(),
-            dbc1: raw_bit1.to_string(),
-            dbc2: raw_bit2.to_string(),
-        });
-    }
-    
-    // Compare signal size
-    if sig1.signal_size() != sig2.signal_size() {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Length".to_string(),
-            dbc1: sig1.signal_size().to_string(),
-            dbc2: sig2.signal_size().to_string(),
-        });
-    }
-    
-    // Compare factor
-    if (sig1.factor() - sig2.factor()).abs() > f64::EPSILON {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Factor".to_string(),
-            dbc1: sig1.factor().to_string(),
-            dbc2: sig2.factor().to_string(),
-        });
-    }
-    
-    // Compare offset
-    if (sig1.offset() - sig2.offset()).abs() > f64::EPSILON {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Offset".to_string(),
-            dbc1: sig1.offset().to_string(),
-            dbc2: sig2.offset().to_string(),
-        });
-    }
-    
-    // Compare min value
-    if (sig1.min() - sig2.min()).abs() > f64::EPSILON {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Min Value".to_string(),
-            dbc1: sig1.min().to_string(),
-            dbc2: sig2.min().to_string(),
-        });
-    }
-    
-    // Compare max value
-    if (sig1.max() - sig2.max()).abs() > f64::EPSILON {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Max Value".to_string(),
-            dbc1: sig1.max().to_string(),
-            dbc2: sig2.max().to_string(),
-        });
-    }
-    
-    // Compare unit
-    let unit1 = if sig1.unit().trim().is_empty() { "No Unit" } else { sig1.unit() };
-    let unit2 = if sig2.unit().trim().is_empty() { "No Unit" } else { sig2.unit() };
-    if unit1 != unit2 {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Unit".to_string(),
-            dbc1: unit1.to_string(),
-            dbc2: unit2.to_string(),
-        });
-    }
-    
-    // Compare byte order
-    if sig1.byte_order() != sig2.byte_order() {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Byte Order".to_string(),
-            dbc1: format!("{:?}", sig1.byte_order()),
-            dbc2: format!("{:?}", sig2.byte_order()),
-        });
-    }
-    
-    // Compare value type
-    if sig1.value_type() != sig2.value_type() {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Value Type".to_string(),
-            dbc1: format!("{:?}", sig1.value_type()),
-            dbc2: format!("{:?}", sig2.value_type()),
-        });
-    }
-    
-    // Compare receivers
-    if sig1.receivers() != sig2.receivers() {
-        let receivers1 = format_receivers(sig1.receivers());
-        let receivers2 = format_receivers(sig2.receivers());
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Receivers".to_string(),
-            dbc1: receivers1,
-            dbc2: receivers2,
-        });
-    }
-    
-    // Compare multiplexer type
-    if sig1.multiplexer_type() != sig2.multiplexer_type() {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Multiplexer Type".to_string(),
-            dbc1: format!("{:?}", sig1.multiplexer_type()),
-            dbc2: format!("{:?}", sig2.multiplexer_type()),
-        });
-    }
-    
-    // Compare initial values
-    let raw_initial1 = sig1.initial_value();
-    let raw_initial2 = sig2.initial_value();
-    let vector_initial1 = sig1.vector_initial_value();
-    let vector_initial2 = sig2.vector_initial_value();
-    
-    let raw_initial_different = (raw_initial1 - raw_initial2).abs() > f64::EPSILON;
-    let vector_initial_different = (vector_initial1 - vector_initial2).abs() > f64::EPSILON;
-    
-    // If Vector initial values are different, show Vector output only
-    if vector_initial_different {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Initial Value (Vector)".to_string(),
-            dbc1: vector_initial1.to_string(),
-            dbc2: vector_initial2.to_string(),
-        });
-    }
-    // If Vector initial values are same but raw initial values are different, show raw output only
-    if raw_initial_different {
-        results.push(ComparisonResult {
-            result_type: "Signal".to_string(),
-            message: msg_name.to_string(),
-            signal: signal_name.to_string(),
-            field: "Initial Value (Raw)".to_string(),
-            dbc1: raw_initial1.to_string(),
-            dbc2: raw_initial2.to_string(),
-        });
-    }
-}
+/// Fills the results table with the results the severity filter lets through.
+/// `project` holds the reviews of compared results and is `None` for lint
+/// problems, which cannot be reviewed.
+fn show_results(ui: &MainWindow, results: &[ComparisonResult], project: Option<&Project>) {
+    let shown = filter_by_severity(results, severity_filter(ui), ui.get_sort_by_severity());
+    let items: Vec<ComparisonResultItem> = shown
+        .iter()
+        .map(|result| {
+            let mut item = ComparisonResultItem::from(result.clone());
+            if let Some(review) = project.and_then(|project| project.review_of(result)) {
+                item.review_status = review_status_index(review.status);
+                item.review = review_text(Some(review)).into();
+            }
+            item
+        })
+        .collect();
+    ui.set_comparison_results(ModelRc::new(VecModel::from(items)));
+    ui.set_selected_row(-1);
+    ui.set_reviewable(project.is_some());
 
-fn export_comparison_to_csv(dbc1: &Dbc, dbc2: &Dbc, path: &str) -> Result<(), Box<dyn Error>> {
-    let mut csv_file = File::create(path)?;
-    writeln!(csv_file, "Type,Message,Signal,Field,DBC1,DBC2")?;
-    compare_dbc_to_csv(dbc1, dbc2, &mut csv_file)?;
-    Ok(())
-}
+    let (action, found, value1, value2) = if project.is_none() {
+        ("Lint", "problems", "Value", "Reference")
+    } else {
+        ("Comparison", "differences", "DBC1", "DBC2")
+    };
+    ui.set_value1_header(value1.into());
+    ui.set_value2_header(value2.into());
 
-fn escape_csv_field(field: &str) -> String {
-    if field.contains(',') || field.contains('"') || field.contains('\n') {
-        format!("\"{}\"", field.replace('"', "\"\""))
+    if shown.len() == results.len() {
+        ui.set_status(format!("{} complete. Found {} {}.", action, results.len(), found).into());
     } else {
-        field.to_string()
+        ui.set_status(format!("{} complete. Showing {} of {} {}.", action, shown.len(), results.len(), found).into());
     }
 }
 
-fn compare_dbc_to_csv(dbc1: &Dbc, dbc2: &Dbc, csv_file: &mut File) -> Result<(), Box<dyn Error>> {
-	
-    // Create maps for quick lookup by message name
-    let mut dbc1_messages: HashMap<String, &rs_dbc::Message> = HashMap::new();
-    let mut dbc2_messages: HashMap<String, &rs_dbc::Message> = HashMap::new();
-    
-    for msg in &dbc1.messages {
-        dbc1_messages.insert(msg.message_name().to_string(), msg);
+/// Index of a review status in the review combo box.
+fn review_status_index(status: ReviewStatus) -> i32 {
+    match status {
+        ReviewStatus::Open => 0,
+        ReviewStatus::Accepted => 1,
+        ReviewStatus::Rejected => 2,
     }
-    
-    for msg in &dbc2.messages {
-        dbc2_messages.insert(msg.message_name().to_string(), msg);
-    }
-    
-    // Get all unique message names
-    let mut all_message_names: HashSet<String> = HashSet::new();
-    all_message_names.extend(dbc1_messages.keys().cloned());
-    all_message_names.extend(dbc2_messages.keys().cloned());
-    
-    // Sort message names alphabetically
-    let mut sorted_message_names: Vec<String> = all_message_names.into_iter().collect();
-    sorted_message_names.sort();
-    
-    for msg_name in &sorted_message_names {
-        let msg1 = dbc1_messages.get(msg_name);
-        let msg2 = dbc2_messages.get(msg_name);
-        
-        match (msg1, msg2) {
-            (Some(m1), Some(m2)) => {
-                // Both DBCs have this message - compare properties
-                compare_message_properties(csv_file, m1, m2)?;
-                compare_signals(csv_file, m1, m2)?;
-            },
-            (Some(m1), None) => {
-                // Only DBC1 has this message
-                write_message_only_in_dbc(csv_file, m1, "DBC1")?;
-            },
-            (None, Some(m2)) => {
-                // Only DBC2 has this message
-                write_message_only_in_dbc(csv_file, m2, "DBC2")?;
-            },
-            (None, None) => unreachable!(),
-        }
-    }
-	
-    Ok(())
 }
 
-fn compare_message_properties(
-    csv_file: &mut File, 
-    msg1: &rs_dbc::Message, 
-    msg2: &rs_dbc::Message
-) -> Result<(), Box<dyn Error>> {
-    
-    let msg_name = msg1.message_name();
-    
-    // Compare message size
-    if msg1.message_size() != msg2.message_size() {
-        writeln!(csv_file, "Message,{},, DLC,{},{}", 
-                msg_name, msg1.message_size(), msg2.message_size())?;
-    }
-    
-    // Compare cycle time
-    if msg1.cycle_time() != msg2.cycle_time() {
-        writeln!(csv_file, "Message,{},, Cycle Time,{},{}", 
-                msg_name, msg1.cycle_time(), msg2.cycle_time())?;
-    }
-    
-    // Compare transmitter
-    if msg1.transmitter() != msg2.transmitter() {
-        writeln!(csv_file, "Message,{},, Transmitter,{},{}", 
-                msg_name, msg1.transmitter(), msg2.transmitter())?;
-    }
-    
-    // Compare message ID
-    let (id1, kind1) = msg1.message_id();
-    let (id2, kind2) = msg2.message_id();
-    if id1 != id2 {
-        writeln!(csv_file, "Message,{},, Message ID,0x{:X},0x{:X}", 
-                msg_name, id1, id2)?;
-    }
-    
-    // Compare message ID kind
-    if kind1 != kind2 {
-        writeln!(csv_file, "Message,{},, ID Format,{},{}", 
-                msg_name, kind1, kind2)?;
+/// Text of the Review column: the status, followed by the note if any.
+fn review_text(review: Option<&Review>) -> String {
+    let Some(review) = review else {
+        return String::new();
+    };
+    let status = match review.status {
+        ReviewStatus::Open => "Open",
+        ReviewStatus::Accepted => "Accepted",
+        ReviewStatus::Rejected => "Rejected",
+    };
+    if review.note.is_empty() {
+        status.to_string()
+    } else {
+        format!("{}: {}", status, review.note)
     }
-    
-    Ok(())
 }
 
-fn compare_signals(
-    csv_file: &mut File, 
-    msg1: &rs_dbc::Message, 
-    msg2: &rs_dbc::Message
-) -> Result<(), Box<dyn Error>> {
-    
-    let msg_name = msg1.message_name();
-    
-    // Create maps for signal lookup
-    let mut signals1: HashMap<&str, &rs_dbc::Signal> = HashMap::new();
-    let mut signals2: HashMap<&str, &rs_dbc::Signal> = HashMap::new();
-    
-    for signal in &msg1.signals {
-        signals1.insert(signal.name(), signal);
-    }
-    
-    for signal in &msg2.signals {
-        signals2.insert(signal.name(), signal);
-    }
-    
-    // Get all unique signal names
-    let mut all_signal_names: HashSet<&str> = HashSet::new();
-    all_signal_names.extend(signals1.keys());
-    all_signal_names.extend(signals2.keys());
-    
-    // Sort signal names alphabetically
-    let mut sorted_signal_names: Vec<&str> = all_signal_names.into_iter().collect();
-    sorted_signal_names.sort();
-    
-    for &signal_name in &sorted_signal_names {
-        let sig1 = signals1.get(signal_name);
-        let sig2 = signals2.get(signal_name);
-        
-        match (sig1, sig2) {
-            (Some(s1), Some(s2)) => {
-                // Both DBCs have this signal - compare properties
-                compare_signal_properties(csv_file, msg_name, s1, s2)?;
-            },
-            (Some(s1), None) => {
-                // Only DBC1 has this signal
-                writeln!(csv_file, "Signal,{},{}, Exists,Yes,No", msg_name, s1.name())?;
-            },
-            (None, Some(s2)) => {
-                // Only DBC2 has this signal
-                writeln!(csv_file, "Signal,{},{}, Exists,No,Yes", msg_name, s2.name())?;
+/// The file pair and options set in the window, as a project without
+/// reviews.
+fn project_from_ui(ui: &MainWindow) -> Result<Project, String> {
+    let tolerances = ui.get_tolerances().parse()?;
+    let threshold = parse_threshold(&ui.get_text_threshold())?;
+    let rules_path = ui.get_rules_path().to_string();
+    let rules = if rules_path.is_empty() {
+        IgnoreRules::default()
+    } else {
+        IgnoreRules::load(&rules_path).map_err(|e| format!("Error loading ignore rules: {}", e))?
+    };
+
+    Ok(Project {
+        dbc1: ui.get_dbc1_path().to_string(),
+        dbc2: ui.get_dbc2_path().to_string(),
+        match_mode: match ui.get_match_mode() {
+            1 => MatchMode::Id,
+            2 => MatchMode::Name,
+            _ => MatchMode::IdThenName,
+        },
+        tolerances,
+        text_matching: TextMatching {
+            strategy: match ui.get_text_match() {
+                1 => TextMatch::Levenshtein,
+                2 => TextMatch::Normalized,
+                3 => TextMatch::CaseInsensitive,
+                4 => TextMatch::Exact,
+                _ => TextMatch::JaroWinkler,
             },
-            (None, None) => unreachable!(),
-        }
-    }
-    
-    Ok(())
+            threshold,
+        },
+        rules,
+        reviews: Vec::new(),
+    })
 }
 
-fn compare_signal_properties(
-    csv_file: &mut File,
-    msg_name: &str,
-    sig1: &rs_dbc::Signal,
-    sig2: &rs_dbc::Signal
-) -> Result<(), Box<dyn Error>> {
-    
-    let signal_name = sig1.name();
-    
-    // Check both raw and Vector start bits
-    let raw_bit1 = sig1.start_bit();
-    let raw_bit2 = sig2.start_bit();
-    let vector_bit1 = sig1.vector_start_bit();
-    let vector_bit2 = sig2.vector_start_bit();
-    
-    let raw_different = raw_bit1 != raw_bit2;
-    let vector_different = vector_bit1 != vector_bit2;
-    
-    // If Vector bits are different, show Vector output only
-    if vector_different {
-        writeln!(csv_file, "Signal,{},{}, Start Bit (Vector),{},{}", 
-                msg_name, signal_name, vector_bit1, vector_bit2)?;
-    }
-    // If Vector bits are same but raw bits are different, show raw output only
-    if raw_different {
-        writeln!(csv_file, "Signal,{},{}, Start Bit (Raw),{},{}", 
-                msg_name, signal_name, raw_bit1, raw_bit2)?;
-    }
-    
-    // Compare signal size
-    if sig1.signal_size() != sig2.signal_size() {
-        writeln!(csv_file, "Signal,{},{}, Length,{},{}", 
-                msg_name, signal_name, sig1.signal_size(), sig2.signal_size())?;
-    }
-    
-    // Compare factor
-    if (sig1.factor() - sig2.factor()).abs() > f64::EPSILON {
-        writeln!(csv_file, "Signal,{},{}, Factor,{},{}", 
-                msg_name, signal_name, sig1.factor(), sig2.factor())?;
-    }
-    
-    // Compare offset
-    if (sig1.offset() - sig2.offset()).abs() > f64::EPSILON {
-        writeln!(csv_file, "Signal,{},{}, Offset,{},{}", 
-                msg_name, signal_name, sig1.offset(), sig2.offset())?;
-    }
-    
-    // Compare min value
-    if (sig1.min() - sig2.min()).abs() > f64::EPSILON {
-        writeln!(csv_file, "Signal,{},{}, Min Value,{},{}", 
-                msg_name, signal_name, sig1.min(), sig2.min())?;
-    }
-    
-    // Compare max value
-    if (sig1.max() - sig2.max()).abs() > f64::EPSILON {
-        writeln!(csv_file, "Signal,{},{}, Max Value,{},{}", 
-                msg_name, signal_name, sig1.max(), sig2.max())?;
-    }
-    
-    // Compare unit
-    let unit1 = if sig1.unit().trim().is_empty() { "No Unit" } else { sig1.unit() };
-    let unit2 = if sig2.unit().trim().is_empty() { "No Unit" } else { sig2.unit() };
-    if unit1 != unit2 {
-        writeln!(csv_file, "Signal,{},{}, Unit,{},{}", 
-                msg_name, signal_name, unit1, unit2)?;
-    }
-    
-    // Compare byte order
-    if sig1.byte_order() != sig2.byte_order() {
-        writeln!(csv_file, "Signal,{},{}, Byte Order,{},{}", 
-                msg_name, signal_name, sig1.byte_order(), sig2.byte_order())?;
-    }
-    
-    // Compare value type
-    if sig1.value_type() != sig2.value_type() {
-        writeln!(csv_file, "Signal,{},{}, Value Type,{},{}", 
-                msg_name, signal_name, sig1.value_type(), sig2.value_type())?;
-    }
-    
-    // Compare receivers
-    if sig1.receivers() != sig2.receivers() {
-        let receivers1 = format_receivers(sig1.receivers());
-        let receivers2 = format_receivers(sig2.receivers());
-        writeln!(csv_file, "Signal,{},{}, Receivers,{},{}", 
-                msg_name, signal_name, receivers1, receivers2)?;
-    }
-    
-    // Compare multiplexer type
-    if sig1.multiplexer_type() != sig2.multiplexer_type() {
-        writeln!(csv_file, "Signal,{},{}, Multiplexer Type,{},{}", 
-                msg_name, signal_name, sig1.multiplexer_type(), sig2.multiplexer_type())?;
-    }
-    
-    // Compare initial values
-    let raw_initial1 = sig1.initial_value();
-    let raw_initial2 = sig2.initial_value();
-    let vector_initial1 = sig1.vector_initial_value();
-    let vector_initial2 = sig2.vector_initial_value();
-    
-    let raw_initial_different = (raw_initial1 - raw_initial2).abs() > f64::EPSILON;
-    let vector_initial_different = (vector_initial1 - vector_initial2).abs() > f64::EPSILON;
-    
-    // If Vector initial values are different, show Vector output only
-    if vector_initial_different {
-        writeln!(csv_file, "Signal,{},{}, Initial Value (Vector),{},{}", 
-                msg_name, signal_name, vector_initial1, vector_initial2)?;
-    }
-    // If Vector initial values are same but raw initial values are different, show raw output only
-    if raw_initial_different {
-        writeln!(csv_file, "Signal,{},{}, Initial Value (Raw),{},{}", 
-                msg_name, signal_name, raw_initial1, raw_initial2)?;
-    }
-    
-    // Compare value descriptions
-    compare_value_descriptions(csv_file, msg_name, signal_name, sig1, sig2)?;
-    
-    Ok(())
+/// Sets the file pair and options of the window to those of the project
+/// at `path`.
+fn show_project(ui: &MainWindow, project: &Project, path: &str) {
+    ui.set_dbc1_path(project.dbc1.as_str().into());
+    ui.set_dbc2_path(project.dbc2.as_str().into());
+    ui.set_match_mode(match project.match_mode {
+        MatchMode::IdThenName => 0,
+        MatchMode::Id => 1,
+        MatchMode::Name => 2,
+    });
+    ui.set_tolerances(project.tolerances.to_string().into());
+    ui.set_text_match(match project.text_matching.strategy {
+        TextMatch::JaroWinkler => 0,
+        TextMatch::Levenshtein => 1,
+        TextMatch::Normalized => 2,
+        TextMatch::CaseInsensitive => 3,
+        TextMatch::Exact => 4,
+    });
+    ui.set_text_threshold(project.text_matching.threshold.to_string().into());
+    // The project file doubles as the rules file when it has rules of its own
+    let rules_path = if project.rules == IgnoreRules::default() { "" } else { path };
+    ui.set_rules_path(rules_path.into());
 }
 
-fn normalize(text: &str) -> String {
-
-    let replaced = text
-        .to_lowercase()
-        .replace('-', " ")
-        .replace('_', " ");
-
-    let mut words: Vec<String> = replaced
-        .to_lowercase()
-        .split_whitespace()
-        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
-        .filter(|word| !word.is_empty())
-        .map(|word| word.to_string())
-        .collect();
-    
-    words.sort(); // Alphabetical sorting
-    words.join(" ")
+/// Hides the payload grids.
+fn clear_layout(ui: &MainWindow) {
+    ui.set_layout_message("".into());
+    ui.set_layout1(ModelRc::default());
+    ui.set_layout2(ModelRc::default());
 }
 
-fn is_equivalent(term1: &str, term2: &str) -> bool {
-    normalize(term1) == normalize(term2)
-}
+/// Fills the payload grids with the signal layout of a message in both
+/// files, pairing the `renames` (DBC1 name, DBC2 name) as the same signal.
+fn show_layout(ui: &MainWindow, name: &str, msg1: Option<&Message>, msg2: Option<&Message>, renames: &[(&str, &str)]) {
+    // Classic CAN payloads show 8 bytes, CAN FD payloads up to 64
+    let size = msg1.iter().chain(msg2.iter()).map(|msg| msg.message_size() as usize).max().unwrap_or(0);
+    let bytes = size.clamp(8, 64);
 
-fn calculate_similarity(s1: &str, s2: &str) -> f64 {
-    // First check if they're equivalent after normalization
-    if is_equivalent(s1, s2) {
-        return 1.0;
-    }
-    
-    // If not equivalent, use Jaro-Winkler for partial similarity
-    let normalized_s1 = normalize(s1);
-    let normalized_s2 = normalize(s2);
-    
-    strsim::jaro_winkler(&normalized_s1, &normalized_s2)
-}
+    let renames1: HashMap<&str, &str> = renames.iter().copied().collect();
+    let renames2: HashMap<&str, &str> = renames.iter().map(|&(name1, name2)| (name2, name1)).collect();
 
-fn compare_value_descriptions(
-    csv_file: &mut File,
-    msg_name: &str,
-    signal_name: &str,
-    sig1: &rs_dbc::Signal,
-    sig2: &rs_dbc::Signal
-) -> Result<(), Box<dyn Error>> {
-    
-    let val_desc1 = sig1.value_descriptions();
-    let val_desc2 = sig2.value_descriptions();
-    
-    // Get all unique values from both signals
-    let mut all_values: HashSet<u64> = HashSet::new();
-    all_values.extend(val_desc1.keys());
-    all_values.extend(val_desc2.keys());
-    
-    // Compare each value description
-    for &value in &all_values {
-        let desc1 = val_desc1.get(&value);
-        let desc2 = val_desc2.get(&value);
-        
-        match (desc1, desc2) {
-            (Some(d1), Some(d2)) => {
-                // Handle empty or whitespace-only descriptions
-                let desc1_display = if d1.trim().is_empty() { "No Description" } else { d1 };
-                let desc2_display = if d2.trim().is_empty() { "No Description" } else { d2 };
-                
-                // Calculate similarity between descriptions using Jaro-Winkler
-                let similarity = calculate_similarity(desc1_display, desc2_display);
-                // Only report as different if similarity is below threshold (0.85 = 85% similar)
-                // Jaro-Winkler is better at handling common prefixes and minor variations
-                if similarity < 0.85 {
-                    writeln!(csv_file, "Signal,{},{}, Value 0x{:X} Description,{},{}", 
-                            msg_name, signal_name, value, 
-                            escape_csv_field(desc1_display), 
-                            escape_csv_field(desc2_display))?;
-                }
-            },
-            (Some(d1), None) => {
-                let desc1_display = if d1.trim().is_empty() { "No Description" } else { d1 };
-                writeln!(csv_file, "Signal,{},{}, Value 0x{:X} Description,{},No Description", 
-                        msg_name, signal_name, value, escape_csv_field(desc1_display))?;
-            },
-            (None, Some(d2)) => {
-                let desc2_display = if d2.trim().is_empty() { "No Description" } else { d2 };
-                writeln!(csv_file, "Signal,{},{}, Value 0x{:X} Description,No Description,{}", 
-                        msg_name, signal_name, value, escape_csv_field(desc2_display))?;
-            },
-            (None, None) => unreachable!(),
-        }
-    }
-    
-    Ok(())
+    ui.set_layout_message(name.into());
+    ui.set_layout1(layout_rows(msg1.map(|msg| payload_map(msg, msg2, &renames1, bytes)), bytes));
+    ui.set_layout2(layout_rows(msg2.map(|msg| payload_map(msg, msg1, &renames2, bytes)), bytes));
 }
 
-fn format_receivers(receivers: &Vec<String>) -> String {
-    if receivers.is_empty() {
-        return "No Receivers".to_string();
-    }
-    
-    let filtered_receivers: Vec<&String> = receivers
-        .iter()
-        .filter(|r| !r.starts_with("Vector__XXX"))
+/// Converts a payload map into grid rows, one per byte, with bit 7 first.
+fn layout_rows(map: Option<Vec<Option<BitOwner>>>, bytes: usize) -> ModelRc<LayoutRow> {
+    let map = map.unwrap_or_else(|| vec![None; bytes * 8]);
+    let rows: Vec<LayoutRow> = (0..bytes)
+        .map(|byte| {
+            let cells: Vec<LayoutCell> = (0..8)
+                .rev()
+                .map(|bit| match map[byte * 8 + bit] {
+                    Some(owner) => LayoutCell {
+                        signal: owner.signal.into(),
+                        change: match owner.change {
+                            SignalChange::Unchanged => 1,
+                            SignalChange::Moved => 2,
+                            SignalChange::Resized => 3,
+                            SignalChange::Unmatched => 4,
+                        },
+                    },
+                    None => LayoutCell { signal: "".into(), change: 0 },
+                })
+                .collect();
+            LayoutRow { byte: byte as i32, cells: ModelRc::new(VecModel::from(cells)) }
+        })
         .collect();
-    
-    if filtered_receivers.is_empty() {
-        "No Receivers".to_string()
-    } else {
-        let joined = filtered_receivers
-            .iter()
-            .map(|s| s.as_str())
-            .collect::<Vec<&str>>()
-            .join(",");
-        
-        // If there are multiple receivers, wrap in quotes to prevent CSV issues
-        if filtered_receivers.len() > 1 {
-            format!("\"{}\"", joined)
-        } else {
-            joined
-        }
-    }
-}
-
-fn write_message_only_in_dbc(
-    csv_file: &mut File,
-    msg: &rs_dbc::Message,
-    dbc_name: &str
-) -> Result<(), Box<dyn Error>> {
-    
-    let msg_name = msg.message_name();
-    let (dbc1_val, dbc2_val) = if dbc_name == "DBC1" { ("Yes", "No") } else { ("No", "Yes") };
-    
-    writeln!(csv_file, "Message,{},, Exists,{},{}", msg_name, dbc1_val, dbc2_val)?;
-    
-    Ok(())
+    ModelRc::new(VecModel::from(rows))
 }
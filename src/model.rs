@@ -0,0 +1,402 @@
+//! Typed model of the differences found between two DBC files.
+//!
+//! Nothing in this module produces display strings; see [`crate::report`]
+//! for how results are rendered into table rows.
+
+use serde::{Deserialize, Serialize};
+use crate::network::{AccessType, AttributeObject, ExtendedValueType, VariableType};
+use crate::tolerance::Tolerance;
+
+/// Kind of DBC object a difference belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EntityKind {
+    /// The network (database) as a whole.
+    Network,
+    /// A network node (ECU) from `BU_`.
+    Node,
+    /// An attribute definition from `BA_DEF_`.
+    Attribute,
+    /// A standalone value table from `VAL_TABLE_`.
+    ValueTable,
+    /// An environment variable from `EV_`.
+    EnvironmentVariable,
+    Message,
+    Signal,
+}
+
+/// How a property changed from the first file to the second.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// Only the second file has the object or property.
+    Added,
+    /// Only the first file has the object or property.
+    Removed,
+    /// Both files have the property with different values.
+    Modified,
+    /// The object was paired with an object of a different name.
+    Renamed,
+    /// A problem found by linting a single file.
+    Problem,
+}
+
+impl ChangeKind {
+    /// Derives the change kind from which side has a value.
+    pub fn between<T>(dbc1: &Option<T>, dbc2: &Option<T>) -> ChangeKind {
+        match (dbc1, dbc2) {
+            (None, Some(_)) => ChangeKind::Added,
+            (Some(_), None) => ChangeKind::Removed,
+            _ => ChangeKind::Modified,
+        }
+    }
+}
+
+/// Property of a DBC object that differs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Field {
+    /// The whole object exists in only one file.
+    Exists,
+    /// Name of an object paired under a different name.
+    Name,
+    Dlc,
+    CycleTime,
+    /// One sender of a message, from `BO_` or `BO_TX_BU_`.
+    Transmitter,
+    MessageId,
+    IdFormat,
+    /// Payload bits a signal occupies, from least to most significant bit.
+    Layout,
+    Length,
+    Factor,
+    Offset,
+    MinValue,
+    MaxValue,
+    /// Physical value range representable by the raw bits, given length,
+    /// signedness, factor and offset.
+    PhysicalRange,
+    /// Whether the declared `[min|max]` fits the physical range.
+    RangeFit,
+    Unit,
+    ValueType,
+    /// Integer, float or double type from `SIG_VALTYPE_`.
+    ExtendedValueType,
+    Receivers,
+    MultiplexerType,
+    /// Multiplexor value that selects a multiplexed signal.
+    MultiplexorValue,
+    /// Name of the multiplexor signal that selects a multiplexed signal.
+    MultiplexorSignal,
+    /// Multiplexor value ranges from `SG_MUL_VAL_`.
+    MultiplexorRanges,
+    /// Physical initial value (raw value scaled by factor and offset).
+    InitialValueVector,
+    /// Raw initial value from `GenSigStartValue`.
+    InitialValueRaw,
+    /// Description of one raw value from `VAL_` or `VAL_TABLE_`.
+    ValueDescription(i64),
+    /// Comment from `CM_`.
+    Comment,
+    /// Value of the named attribute from `BA_`, or its default.
+    Attribute(String),
+    /// Kind of object an attribute definition applies to.
+    AttributeObject,
+    /// Value type keyword of an attribute definition (`INT`, `ENUM`, ...).
+    AttributeType,
+    /// Allowed numeric range of an attribute definition.
+    AttributeRange,
+    /// Allowed values of an enum attribute definition.
+    EnumValues,
+    /// Default value of an attribute from `BA_DEF_DEF_`.
+    AttributeDefault,
+    /// Signals whose value descriptions are taken from a value table.
+    ReferencedBy,
+    /// Member signals of the named signal group from `SIG_GROUP_`.
+    SignalGroup(String),
+    /// Value type of an environment variable.
+    VariableType,
+    /// Initial value of an environment variable.
+    InitialValue,
+    /// Access type of an environment variable.
+    AccessType,
+    /// Nodes allowed to access an environment variable.
+    AccessNodes,
+    /// Length in bytes of a data environment variable from `ENVVAR_DATA_`.
+    DataSize,
+    /// Payload bits a signal shares with another signal present at the same time.
+    Overlap,
+    /// Payload bits a signal occupies beyond the message's DLC.
+    BeyondPayload,
+    /// A CAN ID used by more than one message.
+    DuplicateId,
+    /// A receiver that is not declared in `BU_`.
+    UndeclaredReceiver,
+}
+
+/// Signedness of a signal's raw value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    Unsigned,
+    Signed,
+}
+
+/// Role of a signal in multiplexing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MultiplexerType {
+    Plain,
+    /// The multiplexor (switch) signal of a message.
+    Multiplexer,
+    /// A signal only present for one multiplexor value.
+    Multiplexed,
+}
+
+/// Whether a signal's declared `[min|max]` fits its representable range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RangeFit {
+    /// The declared range is `[0|0]`, meaning no range was specified.
+    Unspecified,
+    Fits,
+    /// The declared range reaches values the raw bits cannot represent.
+    Exceeds,
+}
+
+/// Identifier format of a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IdFormat {
+    Standard,
+    Extended,
+}
+
+impl ValueType {
+    pub(crate) fn from_dbc(value_type: &str) -> ValueType {
+        if value_type == "Signed" { ValueType::Signed } else { ValueType::Unsigned }
+    }
+}
+
+impl MultiplexerType {
+    pub(crate) fn from_dbc(multiplexer_type: &str) -> MultiplexerType {
+        match multiplexer_type {
+            "Multiplexer" => MultiplexerType::Multiplexer,
+            "Multiplexed" => MultiplexerType::Multiplexed,
+            _ => MultiplexerType::Plain,
+        }
+    }
+}
+
+impl IdFormat {
+    pub(crate) fn from_dbc(message_id: &rs_dbc::MessageID) -> IdFormat {
+        match message_id {
+            rs_dbc::MessageID::Standard(_) => IdFormat::Standard,
+            rs_dbc::MessageID::Extended(_) => IdFormat::Extended,
+        }
+    }
+}
+
+/// Value of a property in one of the two files.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Integer(u64),
+    Float(f64),
+    /// A CAN identifier.
+    Id(u32),
+    Text(String),
+    List(Vec<String>),
+    /// An inclusive numeric range.
+    Range(f64, f64),
+    ValueType(ValueType),
+    MultiplexerType(MultiplexerType),
+    IdFormat(IdFormat),
+    AttributeObject(AttributeObject),
+    ExtendedValueType(ExtendedValueType),
+    /// Inclusive ranges of raw values or payload bits.
+    IntegerRanges(Vec<(u64, u64)>),
+    VariableType(VariableType),
+    AccessType(AccessType),
+    RangeFit(RangeFit),
+}
+
+/// Extra information about how a difference was determined.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Detail {
+    /// Confidence between 0.0 and 1.0 that a proposed rename is correct.
+    Confidence(f64),
+    /// Signals, as `Message.Signal`, that use the value table that changed.
+    ReferencedBy(Vec<String>),
+    /// Tolerance the values were compared with, when it is not the default.
+    Tolerance(Tolerance),
+    /// Similarity between 0.0 and 1.0 of two texts that differ, under the
+    /// text matching strategy of the run.
+    Similarity(f64),
+}
+
+/// A single difference between two DBC files.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComparisonResult {
+    /// Kind of object that differs.
+    pub entity: EntityKind,
+    /// Whether the property was added, removed or modified.
+    pub change: ChangeKind,
+    /// Name of the message the difference belongs to, if any.
+    pub message: Option<String>,
+    /// Name of the signal, or `None` for differences above signal level.
+    pub signal: Option<String>,
+    /// Name of an object that is not a message or signal, such as a node.
+    pub object: Option<String>,
+    /// Property that differs.
+    pub field: Field,
+    /// Value in the first file, `None` when it has no value.
+    pub dbc1: Option<Value>,
+    /// Value in the second file, `None` when it has no value.
+    pub dbc2: Option<Value>,
+    /// How the difference was determined, when that is not obvious.
+    pub details: Vec<Detail>,
+    /// Multiplexor value of the page a multiplexed signal is on.
+    pub mux_page: Option<u64>,
+}
+
+impl ComparisonResult {
+    /// Creates a result for a message or signal, deriving the change kind
+    /// from which side has a value.
+    pub fn new(
+        entity: EntityKind,
+        message: &str,
+        signal: Option<&str>,
+        field: Field,
+        dbc1: Option<Value>,
+        dbc2: Option<Value>,
+    ) -> ComparisonResult {
+        ComparisonResult {
+            entity,
+            change: ChangeKind::between(&dbc1, &dbc2),
+            message: Some(message.to_string()),
+            signal: signal.map(str::to_string),
+            object: None,
+            field,
+            dbc1,
+            dbc2,
+            details: Vec::new(),
+            mux_page: None,
+        }
+    }
+
+    /// Creates a result for a message or signal that exists in only one file.
+    pub fn exists(entity: EntityKind, message: &str, signal: Option<&str>, change: ChangeKind) -> ComparisonResult {
+        ComparisonResult {
+            entity,
+            change,
+            message: Some(message.to_string()),
+            signal: signal.map(str::to_string),
+            object: None,
+            field: Field::Exists,
+            dbc1: None,
+            dbc2: None,
+            details: Vec::new(),
+            mux_page: None,
+        }
+    }
+
+    /// Creates a result for an object paired with an object named `new_name`.
+    pub fn renamed(entity: EntityKind, message: &str, signal: Option<&str>, new_name: &str) -> ComparisonResult {
+        let old_name = signal.unwrap_or(message);
+        ComparisonResult {
+            entity,
+            change: ChangeKind::Renamed,
+            message: Some(message.to_string()),
+            signal: signal.map(str::to_string),
+            object: None,
+            field: Field::Name,
+            dbc1: Some(Value::Text(old_name.to_string())),
+            dbc2: Some(Value::Text(new_name.to_string())),
+            details: Vec::new(),
+            mux_page: None,
+        }
+    }
+
+    /// Creates a result for a named object outside any message, deriving the
+    /// change kind from which side has a value.
+    pub fn for_object(
+        entity: EntityKind,
+        object: &str,
+        field: Field,
+        dbc1: Option<Value>,
+        dbc2: Option<Value>,
+    ) -> ComparisonResult {
+        ComparisonResult {
+            entity,
+            change: ChangeKind::between(&dbc1, &dbc2),
+            message: None,
+            signal: None,
+            object: Some(object.to_string()),
+            field,
+            dbc1,
+            dbc2,
+            details: Vec::new(),
+            mux_page: None,
+        }
+    }
+
+    /// Creates a result for the network as a whole, deriving the change kind
+    /// from which side has a value.
+    pub fn for_network(field: Field, dbc1: Option<Value>, dbc2: Option<Value>) -> ComparisonResult {
+        ComparisonResult {
+            entity: EntityKind::Network,
+            change: ChangeKind::between(&dbc1, &dbc2),
+            message: None,
+            signal: None,
+            object: None,
+            field,
+            dbc1,
+            dbc2,
+            details: Vec::new(),
+            mux_page: None,
+        }
+    }
+
+    /// Creates a result for a named object outside any message that exists
+    /// in only one file.
+    pub fn object_exists(entity: EntityKind, object: &str, change: ChangeKind) -> ComparisonResult {
+        ComparisonResult {
+            change,
+            ..ComparisonResult::for_object(entity, object, Field::Exists, None, None)
+        }
+    }
+
+    /// Creates a result for a problem found by linting a single file.
+    ///
+    /// `value` is what was found and `reference` what it conflicts with,
+    /// such as the other signal of an overlap.
+    pub fn problem(
+        entity: EntityKind,
+        message: &str,
+        signal: Option<&str>,
+        field: Field,
+        value: Option<Value>,
+        reference: Option<Value>,
+    ) -> ComparisonResult {
+        ComparisonResult {
+            change: ChangeKind::Problem,
+            ..ComparisonResult::new(entity, message, signal, field, value, reference)
+        }
+    }
+
+    /// Attaches the multiplexor value of the page the signal is on.
+    pub fn with_mux_page(mut self, mux_page: Option<u64>) -> ComparisonResult {
+        self.mux_page = mux_page;
+        self
+    }
+
+    /// The sender a [`Field::Transmitter`] difference is about. Senders are
+    /// reported one at a time, so this tells such differences apart.
+    pub fn sender(&self) -> Option<&str> {
+        match (&self.field, self.dbc1.as_ref().or(self.dbc2.as_ref())) {
+            (Field::Transmitter, Some(Value::Text(sender))) => Some(sender),
+            _ => None,
+        }
+    }
+
+    /// Attaches a detail explaining how the difference was determined.
+    pub fn with_detail(mut self, detail: Detail) -> ComparisonResult {
+        self.details.push(detail);
+        self
+    }
+}
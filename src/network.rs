@@ -0,0 +1,679 @@
+//! DBC content beyond messages and signals.
+//!
+//! `rs_dbc` only parses messages and their signals. The network-level
+//! sections TwinCAN also compares are parsed here from the same file text.
+
+use std::collections::{BTreeMap, HashMap};
+use regex::{Captures, Regex};
+use rs_dbc::Dbc;
+
+/// A parsed DBC file: messages and signals from `rs_dbc` plus the
+/// network-level content parsed by TwinCAN.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DbcFile {
+    pub dbc: Dbc,
+    pub network: Network,
+}
+
+/// Network-level content of a DBC file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Network {
+    /// Node names from `BU_`, in file order.
+    pub nodes: Vec<String>,
+    /// Message IDs as written in the file and names from `BO_`, in file
+    /// order. Unlike `rs_dbc`, keeps every message sharing an ID.
+    pub messages: Vec<(u32, String)>,
+    /// Attribute definitions from `BA_DEF_`, keyed by attribute name.
+    pub attribute_definitions: BTreeMap<String, AttributeDefinition>,
+    /// Attribute defaults from `BA_DEF_DEF_`, keyed by attribute name.
+    pub attribute_defaults: BTreeMap<String, AttributeValue>,
+    /// Attribute values from `BA_`, keyed by the object they are set on.
+    pub attribute_values: HashMap<ObjectRef, BTreeMap<String, AttributeValue>>,
+    /// Comments from `CM_`, keyed by the object they describe.
+    pub comments: HashMap<ObjectRef, String>,
+    /// Value tables from `VAL_TABLE_`, keyed by table name, each mapping raw
+    /// values to their descriptions.
+    pub value_tables: BTreeMap<String, BTreeMap<i64, String>>,
+    /// Value descriptions from `VAL_`, keyed by signal. `rs_dbc` drops the
+    /// sign of negative raw values, so they are read again here.
+    pub value_descriptions: HashMap<ObjectRef, BTreeMap<i64, String>>,
+    /// Signal groups from `SIG_GROUP_`, keyed by message ID as written in the
+    /// file, then by group name, each listing its member signals.
+    pub signal_groups: HashMap<u32, BTreeMap<String, Vec<String>>>,
+    /// Extended value types from `SIG_VALTYPE_`, keyed by signal. Signals
+    /// without an entry are integers.
+    pub signal_value_types: HashMap<ObjectRef, ExtendedValueType>,
+    /// Multiplexing indicators from `SG_` lines, keyed by signal. Plain
+    /// signals have no entry.
+    pub signal_multiplexing: HashMap<ObjectRef, Multiplexing>,
+    /// Multiplexor signals (`M` or `m<n>M`) from `SG_` lines, keyed by
+    /// message ID as written in the file, in file order.
+    pub multiplexors: HashMap<u32, Vec<String>>,
+    /// Extended multiplexing from `SG_MUL_VAL_`, keyed by multiplexed signal.
+    pub extended_multiplexing: HashMap<ObjectRef, ExtendedMultiplexing>,
+    /// Additional transmitters from `BO_TX_BU_`, keyed by message ID as
+    /// written in the file.
+    pub message_transmitters: HashMap<u32, Vec<String>>,
+    /// Environment variables from `EV_` and `ENVVAR_DATA_`, keyed by name.
+    pub environment_variables: BTreeMap<String, EnvironmentVariable>,
+}
+
+/// Kind of object an attribute definition applies to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AttributeObject {
+    Network,
+    Node,
+    Message,
+    Signal,
+    EnvironmentVariable,
+}
+
+/// Value type and allowed values of an attribute definition.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeType {
+    Int { min: f64, max: f64 },
+    Hex { min: f64, max: f64 },
+    Float { min: f64, max: f64 },
+    String,
+    Enum(Vec<String>),
+}
+
+/// An attribute definition from `BA_DEF_`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeDefinition {
+    pub object: AttributeObject,
+    pub value_type: AttributeType,
+}
+
+/// A value from `BA_` or `BA_DEF_DEF_`. Enum values are stored as their index.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeValue {
+    Number(f64),
+    Text(String),
+}
+
+/// Extended value type of a signal from `SIG_VALTYPE_`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ExtendedValueType {
+    #[default]
+    Integer,
+    /// 32-bit IEEE float.
+    Float,
+    /// 64-bit IEEE double.
+    Double,
+}
+
+/// Multiplexing indicator of a signal in its `SG_` line.
+///
+/// `rs_dbc` only reports whether a signal is a multiplexor or multiplexed,
+/// so the indicator is read again here to get the multiplexor value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Multiplexing {
+    /// The signal is a multiplexor (`M`, or `m<n>M` with extended multiplexing).
+    pub is_multiplexor: bool,
+    /// Multiplexor value that selects the signal (`m<n>`).
+    pub multiplexor_value: Option<u64>,
+}
+
+/// Extended multiplexing of a signal from `SG_MUL_VAL_`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtendedMultiplexing {
+    /// Name of the multiplexor signal that selects the signal.
+    pub multiplexor: String,
+    /// Inclusive ranges of multiplexor values that select the signal.
+    pub ranges: Vec<(u64, u64)>,
+}
+
+/// An environment variable from `EV_`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnvironmentVariable {
+    pub variable_type: VariableType,
+    pub min: f64,
+    pub max: f64,
+    pub unit: String,
+    pub initial_value: f64,
+    pub access_type: AccessType,
+    /// Nodes allowed to access the variable, in file order.
+    pub access_nodes: Vec<String>,
+    /// Length in bytes from `ENVVAR_DATA_`, for data variables.
+    pub data_size: Option<u64>,
+}
+
+/// Value type of an environment variable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VariableType {
+    Integer,
+    Float,
+    String,
+}
+
+/// Access type of an environment variable from its `DUMMY_NODE_VECTOR` keyword.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccessType {
+    Unrestricted,
+    Read,
+    Write,
+    ReadWrite,
+}
+
+/// Reference to a DBC object by the keys used in `BA_` and `CM_` lines.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ObjectRef {
+    Network,
+    Node(String),
+    /// A message by its ID as written in the file.
+    Message(u32),
+    /// A signal by message ID and signal name.
+    Signal(u32, String),
+    EnvironmentVariable(String),
+}
+
+impl Network {
+    /// Parses the network-level sections from DBC file text.
+    pub fn parse(dbc_input: &str) -> Network {
+        let (signal_multiplexing, multiplexors) = parse_signal_multiplexing(dbc_input);
+        Network {
+            nodes: parse_nodes(dbc_input),
+            messages: parse_messages(dbc_input),
+            attribute_definitions: parse_attribute_definitions(dbc_input),
+            attribute_defaults: parse_attribute_defaults(dbc_input),
+            attribute_values: parse_attribute_values(dbc_input),
+            comments: parse_comments(dbc_input),
+            value_tables: parse_value_tables(dbc_input),
+            value_descriptions: parse_value_descriptions(dbc_input),
+            signal_groups: parse_signal_groups(dbc_input),
+            signal_value_types: parse_signal_value_types(dbc_input),
+            signal_multiplexing,
+            multiplexors,
+            extended_multiplexing: parse_extended_multiplexing(dbc_input),
+            message_transmitters: parse_message_transmitters(dbc_input),
+            environment_variables: parse_environment_variables(dbc_input),
+        }
+    }
+
+    /// Attribute values set on `target`, empty when none are set.
+    pub fn attributes_of(&self, target: &ObjectRef) -> BTreeMap<String, AttributeValue> {
+        self.attribute_values.get(target).cloned().unwrap_or_default()
+    }
+
+    /// Extended value type of a signal, `Integer` when none is declared.
+    pub fn value_type_of(&self, target: &ObjectRef) -> ExtendedValueType {
+        self.signal_value_types.get(target).copied().unwrap_or_default()
+    }
+
+    /// Multiplexing of a signal, plain when it has no indicator.
+    pub fn multiplexing_of(&self, target: &ObjectRef) -> Multiplexing {
+        self.signal_multiplexing.get(target).copied().unwrap_or_default()
+    }
+
+    /// Name of the multiplexor signal that selects a multiplexed signal.
+    ///
+    /// This is the multiplexor named in `SG_MUL_VAL_` when the signal has
+    /// extended multiplexing, otherwise the multiplexor of its message. When
+    /// a message has several, the first plain `M` signal is taken, or else
+    /// the first `m<n>M` signal, in file order.
+    pub fn multiplexor_of(&self, message_id: u32, signal: &str) -> Option<&str> {
+        let target = ObjectRef::Signal(message_id, signal.to_string());
+        if let Some(extended) = self.extended_multiplexing.get(&target) {
+            return Some(&extended.multiplexor);
+        }
+        self.multiplexing_of(&target).multiplexor_value?;
+
+        let multiplexors = self.multiplexors.get(&message_id)?;
+        let is_plain = |name: &&String| {
+            self.multiplexing_of(&ObjectRef::Signal(message_id, name.to_string())).multiplexor_value.is_none()
+        };
+        multiplexors.iter().find(is_plain).or(multiplexors.first()).map(String::as_str)
+    }
+
+    /// Value descriptions of a signal, empty when it has none.
+    pub fn value_descriptions_of(&self, target: &ObjectRef) -> BTreeMap<i64, String> {
+        self.value_descriptions.get(target).cloned().unwrap_or_default()
+    }
+
+    /// Comment on `target`, if it has one.
+    pub fn comment_of(&self, target: &ObjectRef) -> Option<&str> {
+        self.comments.get(target).map(String::as_str)
+    }
+}
+
+fn parse_nodes(dbc_input: &str) -> Vec<String> {
+    let re_nodes = Regex::new(r"(?m)^BU_\s*:([^\r\n]*)").unwrap();
+    re_nodes
+        .captures(dbc_input)
+        .map(|cap| cap[1].split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn parse_messages(dbc_input: &str) -> Vec<(u32, String)> {
+    let re_message = Regex::new(r"(?m)^BO_\s+(\d+)\s+(\w+)\s*:").unwrap();
+    re_message
+        .captures_iter(dbc_input)
+        .filter_map(|cap| Some((cap[1].parse::<u32>().ok()?, cap[2].to_string())))
+        .collect()
+}
+
+fn parse_attribute_definitions(dbc_input: &str) -> BTreeMap<String, AttributeDefinition> {
+    let re_definition = Regex::new(r#"(?m)^BA_DEF_\s+(BU_|BO_|SG_|EV_)?\s*"([^"]+)"\s+(INT|HEX|FLOAT|STRING|ENUM)\s*([^;]*);"#).unwrap();
+    let re_enum_value = Regex::new(r#""([^"]*)""#).unwrap();
+    let mut map = BTreeMap::new();
+
+    for cap in re_definition.captures_iter(dbc_input) {
+        let object = match cap.get(1).map(|m| m.as_str()) {
+            Some("BU_") => AttributeObject::Node,
+            Some("BO_") => AttributeObject::Message,
+            Some("SG_") => AttributeObject::Signal,
+            Some("EV_") => AttributeObject::EnvironmentVariable,
+            _ => AttributeObject::Network,
+        };
+
+        let mut range = cap[4].split_whitespace().filter_map(|bound| bound.parse::<f64>().ok());
+        let (min, max) = (range.next().unwrap_or(0.0), range.next().unwrap_or(0.0));
+        let value_type = match &cap[3] {
+            "INT" => AttributeType::Int { min, max },
+            "HEX" => AttributeType::Hex { min, max },
+            "FLOAT" => AttributeType::Float { min, max },
+            "ENUM" => AttributeType::Enum(
+                re_enum_value.captures_iter(&cap[4]).map(|value| value[1].to_string()).collect(),
+            ),
+            _ => AttributeType::String,
+        };
+
+        map.insert(cap[2].to_string(), AttributeDefinition { object, value_type });
+    }
+    map
+}
+
+fn parse_attribute_defaults(dbc_input: &str) -> BTreeMap<String, AttributeValue> {
+    let re_default = Regex::new(r#"(?m)^BA_DEF_DEF_\s+"([^"]+)"\s+("[^"]*"|[^;\s]+)\s*;"#).unwrap();
+    let mut map = BTreeMap::new();
+
+    for cap in re_default.captures_iter(dbc_input) {
+        map.insert(cap[1].to_string(), parse_attribute_value(&cap[2]));
+    }
+    map
+}
+
+fn parse_attribute_values(dbc_input: &str) -> HashMap<ObjectRef, BTreeMap<String, AttributeValue>> {
+    let re_value = Regex::new(
+        r#"(?m)^BA_\s+"([^"]+)"\s+(?:BU_\s+(\w+)\s+|BO_\s+(\d+)\s+|SG_\s+(\d+)\s+(\w+)\s+|EV_\s+(\w+)\s+)?("[^"]*"|[^;\s]+)\s*;"#,
+    ).unwrap();
+    let mut map: HashMap<ObjectRef, BTreeMap<String, AttributeValue>> = HashMap::new();
+
+    for cap in re_value.captures_iter(dbc_input) {
+        if let Some(target) = parse_object_ref(&cap, 2) {
+            map.entry(target).or_default().insert(cap[1].to_string(), parse_attribute_value(&cap[7]));
+        }
+    }
+    map
+}
+
+fn parse_comments(dbc_input: &str) -> HashMap<ObjectRef, String> {
+    let re_comment = Regex::new(
+        r#"(?ms)^CM_\s+(?:BU_\s+(\w+)\s+|BO_\s+(\d+)\s+|SG_\s+(\d+)\s+(\w+)\s+|EV_\s+(\w+)\s+)?"((?:[^"\\]|\\.)*)"\s*;"#,
+    ).unwrap();
+    let mut map = HashMap::new();
+
+    for cap in re_comment.captures_iter(dbc_input) {
+        if let Some(target) = parse_object_ref(&cap, 1) {
+            map.insert(target, cap[6].replace("\\\"", "\""));
+        }
+    }
+    map
+}
+
+fn parse_value_tables(dbc_input: &str) -> BTreeMap<String, BTreeMap<i64, String>> {
+    let re_table = Regex::new(r#"(?m)^VAL_TABLE_\s+(\w+)\s*((?:-?\d+\s+"[^"]*"\s*)*);"#).unwrap();
+    let mut map = BTreeMap::new();
+
+    for cap in re_table.captures_iter(dbc_input) {
+        map.insert(cap[1].to_string(), parse_value_entries(&cap[2]));
+    }
+    map
+}
+
+fn parse_value_descriptions(dbc_input: &str) -> HashMap<ObjectRef, BTreeMap<i64, String>> {
+    let re_descriptions = Regex::new(r#"(?m)^VAL_\s+(\d+)\s+(\w+)\s*((?:-?\d+\s+"[^"]*"\s*)*);"#).unwrap();
+    let mut map = HashMap::new();
+
+    for cap in re_descriptions.captures_iter(dbc_input) {
+        if let Ok(id) = cap[1].parse::<u32>() {
+            map.insert(ObjectRef::Signal(id, cap[2].to_string()), parse_value_entries(&cap[3]));
+        }
+    }
+    map
+}
+
+/// Parses the `<raw value> "<description>"` entries of a `VAL_` or
+/// `VAL_TABLE_` line.
+fn parse_value_entries(entries: &str) -> BTreeMap<i64, String> {
+    let re_entry = Regex::new(r#"(-?\d+)\s+"([^"]*)""#).unwrap();
+    re_entry
+        .captures_iter(entries)
+        .filter_map(|entry| Some((entry[1].parse::<i64>().ok()?, entry[2].to_string())))
+        .collect()
+}
+
+fn parse_signal_groups(dbc_input: &str) -> HashMap<u32, BTreeMap<String, Vec<String>>> {
+    let re_group = Regex::new(r"(?m)^SIG_GROUP_\s+(\d+)\s+(\w+)\s+\d+\s*:([^;]*);").unwrap();
+    let mut map: HashMap<u32, BTreeMap<String, Vec<String>>> = HashMap::new();
+
+    for cap in re_group.captures_iter(dbc_input) {
+        if let Ok(id) = cap[1].parse::<u32>() {
+            let signals = cap[3].split_whitespace().map(str::to_string).collect();
+            map.entry(id).or_default().insert(cap[2].to_string(), signals);
+        }
+    }
+    map
+}
+
+fn parse_signal_value_types(dbc_input: &str) -> HashMap<ObjectRef, ExtendedValueType> {
+    let re_value_type = Regex::new(r"(?m)^SIG_VALTYPE_\s+(\d+)\s+(\w+)\s*:?\s*([012])\s*;").unwrap();
+    let mut map = HashMap::new();
+
+    for cap in re_value_type.captures_iter(dbc_input) {
+        let value_type = match &cap[3] {
+            "1" => ExtendedValueType::Float,
+            "2" => ExtendedValueType::Double,
+            _ => ExtendedValueType::Integer,
+        };
+        if let Ok(id) = cap[1].parse::<u32>() {
+            map.insert(ObjectRef::Signal(id, cap[2].to_string()), value_type);
+        }
+    }
+    map
+}
+
+/// Parses the multiplexing indicators of signals, and the multiplexors of
+/// each message in file order.
+fn parse_signal_multiplexing(dbc_input: &str) -> (HashMap<ObjectRef, Multiplexing>, HashMap<u32, Vec<String>>) {
+    let re_message = Regex::new(r"^\s*BO_\s+(\d+)\s+\w+\s*:").unwrap();
+    let re_signal = Regex::new(r"^\s*SG_\s+(\w+)\s+(M|m(\d+)(M?))\s*:").unwrap();
+    let mut map = HashMap::new();
+    let mut multiplexors: HashMap<u32, Vec<String>> = HashMap::new();
+    let mut message_id = None;
+
+    for line in dbc_input.lines() {
+        if let Some(cap) = re_message.captures(line) {
+            message_id = cap[1].parse::<u32>().ok();
+        } else if let (Some(cap), Some(id)) = (re_signal.captures(line), message_id) {
+            let multiplexing = Multiplexing {
+                is_multiplexor: &cap[2] == "M" || cap.get(4).is_some_and(|m| !m.is_empty()),
+                multiplexor_value: cap.get(3).and_then(|value| value.as_str().parse().ok()),
+            };
+            if multiplexing.is_multiplexor {
+                multiplexors.entry(id).or_default().push(cap[1].to_string());
+            }
+            map.insert(ObjectRef::Signal(id, cap[1].to_string()), multiplexing);
+        }
+    }
+    (map, multiplexors)
+}
+
+fn parse_extended_multiplexing(dbc_input: &str) -> HashMap<ObjectRef, ExtendedMultiplexing> {
+    let re_mul_val = Regex::new(r"(?m)^SG_MUL_VAL_\s+(\d+)\s+(\w+)\s+(\w+)\s+([^;]*);").unwrap();
+    let re_range = Regex::new(r"(\d+)\s*-\s*(\d+)").unwrap();
+    let mut map = HashMap::new();
+
+    for cap in re_mul_val.captures_iter(dbc_input) {
+        let ranges = re_range
+            .captures_iter(&cap[4])
+            .filter_map(|range| Some((range[1].parse().ok()?, range[2].parse().ok()?)))
+            .collect();
+        if let Ok(id) = cap[1].parse::<u32>() {
+            map.insert(
+                ObjectRef::Signal(id, cap[2].to_string()),
+                ExtendedMultiplexing { multiplexor: cap[3].to_string(), ranges },
+            );
+        }
+    }
+    map
+}
+
+fn parse_message_transmitters(dbc_input: &str) -> HashMap<u32, Vec<String>> {
+    let re_transmitters = Regex::new(r"(?m)^BO_TX_BU_\s+(\d+)\s*:([^;]*);").unwrap();
+    let mut map: HashMap<u32, Vec<String>> = HashMap::new();
+
+    for cap in re_transmitters.captures_iter(dbc_input) {
+        if let Ok(id) = cap[1].parse::<u32>() {
+            let nodes = cap[2].split(',').map(str::trim).filter(|node| !node.is_empty()).map(str::to_string);
+            map.entry(id).or_default().extend(nodes);
+        }
+    }
+    map
+}
+
+fn parse_environment_variables(dbc_input: &str) -> BTreeMap<String, EnvironmentVariable> {
+    let re_variable = Regex::new(
+        r#"(?m)^EV_\s+(\w+)\s*:\s*(\d+)\s*\[\s*([^|\]]+)\|([^\]]+)\]\s*"([^"]*)"\s+(\S+)\s+\d+\s+DUMMY_NODE_VECTOR([0-9A-Fa-f]+)\s*([^;]*);"#,
+    ).unwrap();
+    let re_data = Regex::new(r"(?m)^ENVVAR_DATA_\s+(\w+)\s*:\s*(\d+)\s*;").unwrap();
+    let mut map = BTreeMap::new();
+
+    for cap in re_variable.captures_iter(dbc_input) {
+        // Bit 15 of the access type marks a string variable, the low bits the access
+        let access = u32::from_str_radix(&cap[7], 16).unwrap_or(0);
+        let variable_type = match &cap[2] {
+            _ if access & 0x8000 != 0 => VariableType::String,
+            "1" => VariableType::Float,
+            "2" => VariableType::String,
+            _ => VariableType::Integer,
+        };
+        let access_type = match access & 0x3 {
+            1 => AccessType::Read,
+            2 => AccessType::Write,
+            3 => AccessType::ReadWrite,
+            _ => AccessType::Unrestricted,
+        };
+
+        map.insert(cap[1].to_string(), EnvironmentVariable {
+            variable_type,
+            min: cap[3].trim().parse().unwrap_or(0.0),
+            max: cap[4].trim().parse().unwrap_or(0.0),
+            unit: cap[5].to_string(),
+            initial_value: cap[6].parse().unwrap_or(0.0),
+            access_type,
+            access_nodes: cap[8].split(',').map(str::trim).filter(|node| !node.is_empty()).map(str::to_string).collect(),
+            data_size: None,
+        });
+    }
+
+    for cap in re_data.captures_iter(dbc_input) {
+        if let Some(variable) = map.get_mut(&cap[1]) {
+            variable.data_size = cap[2].parse().ok();
+        }
+    }
+    map
+}
+
+/// Reads the object reference captured by the groups starting at `first`:
+/// node, message ID, message ID and signal, then environment variable.
+fn parse_object_ref(cap: &Captures, first: usize) -> Option<ObjectRef> {
+    let group = |offset: usize| cap.get(first + offset).map(|m| m.as_str());
+
+    if let Some(node) = group(0) {
+        Some(ObjectRef::Node(node.to_string()))
+    } else if let Some(id) = group(1) {
+        id.parse().ok().map(ObjectRef::Message)
+    } else if let (Some(id), Some(signal)) = (group(2), group(3)) {
+        id.parse().ok().map(|id| ObjectRef::Signal(id, signal.to_string()))
+    } else if let Some(env_var) = group(4) {
+        Some(ObjectRef::EnvironmentVariable(env_var.to_string()))
+    } else {
+        Some(ObjectRef::Network)
+    }
+}
+
+/// Parses a quoted string or a number from an attribute line.
+fn parse_attribute_value(value: &str) -> AttributeValue {
+    if let Some(text) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        AttributeValue::Text(text.to_string())
+    } else {
+        value.parse::<f64>().map(AttributeValue::Number).unwrap_or_else(|_| AttributeValue::Text(value.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTIPLEXORS: &str = "\
+BO_ 100 Muxed: 8 ECU
+ SG_ Sub m1M : 8|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Mux M : 0|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Sub2 m2M : 8|8@1+ (1,0) [0|255] \"\" PC
+ SG_ A m1 : 16|8@1+ (1,0) [0|255] \"\" PC
+";
+
+    #[test]
+    fn multiplexors_are_kept_in_file_order() {
+        let network = Network::parse(MULTIPLEXORS);
+        assert_eq!(network.multiplexors[&100], vec!["Sub", "Mux", "Sub2"]);
+    }
+
+    #[test]
+    fn multiplexor_of_prefers_the_plain_multiplexor() {
+        // Each parse hashes with new keys, so an order-dependent lookup would vary
+        for _ in 0..20 {
+            let network = Network::parse(MULTIPLEXORS);
+            assert_eq!(network.multiplexor_of(100, "A"), Some("Mux"));
+            assert_eq!(network.multiplexor_of(100, "Mux"), None);
+        }
+    }
+
+    const NETWORK: &str = "\
+BU_: ECU1 ECU2 PC
+
+VAL_TABLE_ OnOff 1 \"On\" 0 \"Off\" ;
+
+BO_ 100 First: 8 ECU1
+ SG_ Speed : 0|16@1+ (0.1,0) [0|6553.5] \"km/h\" PC
+ SG_ Switch : 16|1@1+ (1,0) [0|1] \"\" PC
+
+BO_ 2147484160 Extended: 8 ECU2
+ SG_ Level : 0|32@1- (1,0) [0|0] \"\" PC
+
+BO_TX_BU_ 100 : ECU1,ECU2;
+
+EV_ Gear: 0 [0|5] \"\" 1 7 DUMMY_NODE_VECTOR3 ECU1,PC;
+EV_ Blob: 0 [0|0] \"\" 0 8 DUMMY_NODE_VECTOR8000 Vector__XXX;
+ENVVAR_DATA_ Blob: 4;
+
+CM_ \"The network\";
+CM_ BU_ ECU1 \"Engine \\\"main\\\" unit\";
+CM_ SG_ 100 Speed \"Vehicle
+speed\";
+
+BA_DEF_ BO_ \"GenMsgCycleTime\" INT 0 10000;
+BA_DEF_ SG_ \"GenSigSendType\" ENUM \"Cyclic\",\"OnChange\";
+BA_DEF_ \"BusType\" STRING ;
+BA_DEF_DEF_ \"GenMsgCycleTime\" 100;
+BA_DEF_DEF_ \"BusType\" \"CAN\";
+BA_ \"GenMsgCycleTime\" BO_ 100 20;
+BA_ \"GenSigSendType\" SG_ 100 Switch 1;
+BA_ \"BusType\" \"CAN FD\";
+
+SIG_GROUP_ 100 Drive 1 : Speed Switch;
+SIG_VALTYPE_ 2147484160 Level : 1;
+";
+
+    #[test]
+    fn parse_reads_nodes_and_messages_in_file_order() {
+        let network = Network::parse(NETWORK);
+        assert_eq!(network.nodes, vec!["ECU1", "ECU2", "PC"]);
+        assert_eq!(network.messages, vec![(100, "First".to_string()), (2147484160, "Extended".to_string())]);
+        assert_eq!(network.message_transmitters[&100], vec!["ECU1", "ECU2"]);
+        assert_eq!(network.value_tables["OnOff"], BTreeMap::from([(0, "Off".to_string()), (1, "On".to_string())]));
+        assert_eq!(network.signal_groups[&100]["Drive"], vec!["Speed", "Switch"]);
+        assert_eq!(network.value_type_of(&ObjectRef::Signal(2147484160, "Level".to_string())), ExtendedValueType::Float);
+        assert_eq!(network.value_type_of(&ObjectRef::Signal(100, "Speed".to_string())), ExtendedValueType::Integer);
+    }
+
+    #[test]
+    fn parse_reads_comments_by_object() {
+        let network = Network::parse(NETWORK);
+        assert_eq!(network.comment_of(&ObjectRef::Network), Some("The network"));
+        assert_eq!(network.comment_of(&ObjectRef::Node("ECU1".to_string())), Some("Engine \"main\" unit"));
+        assert_eq!(network.comment_of(&ObjectRef::Signal(100, "Speed".to_string())), Some("Vehicle\nspeed"));
+        assert_eq!(network.comment_of(&ObjectRef::Message(100)), None);
+    }
+
+    #[test]
+    fn parse_reads_attributes() {
+        let network = Network::parse(NETWORK);
+        assert_eq!(
+            network.attribute_definitions["GenMsgCycleTime"],
+            AttributeDefinition { object: AttributeObject::Message, value_type: AttributeType::Int { min: 0.0, max: 10000.0 } },
+        );
+        assert_eq!(
+            network.attribute_definitions["GenSigSendType"].value_type,
+            AttributeType::Enum(vec!["Cyclic".to_string(), "OnChange".to_string()]),
+        );
+        assert_eq!(network.attribute_definitions["BusType"].object, AttributeObject::Network);
+        assert_eq!(network.attribute_defaults["GenMsgCycleTime"], AttributeValue::Number(100.0));
+        assert_eq!(network.attribute_defaults["BusType"], AttributeValue::Text("CAN".to_string()));
+        assert_eq!(network.attributes_of(&ObjectRef::Message(100))["GenMsgCycleTime"], AttributeValue::Number(20.0));
+        assert_eq!(
+            network.attributes_of(&ObjectRef::Signal(100, "Switch".to_string()))["GenSigSendType"],
+            AttributeValue::Number(1.0),
+        );
+        assert_eq!(network.attributes_of(&ObjectRef::Network)["BusType"], AttributeValue::Text("CAN FD".to_string()));
+        assert!(network.attributes_of(&ObjectRef::Message(2147484160)).is_empty());
+    }
+
+    #[test]
+    fn parse_reads_environment_variables() {
+        let network = Network::parse(NETWORK);
+        assert_eq!(network.environment_variables["Gear"], EnvironmentVariable {
+            variable_type: VariableType::Integer,
+            min: 0.0,
+            max: 5.0,
+            unit: String::new(),
+            initial_value: 1.0,
+            access_type: AccessType::ReadWrite,
+            access_nodes: vec!["ECU1".to_string(), "PC".to_string()],
+            data_size: None,
+        });
+        let blob = &network.environment_variables["Blob"];
+        assert_eq!(blob.variable_type, VariableType::String);
+        assert_eq!(blob.access_type, AccessType::Unrestricted);
+        assert_eq!(blob.data_size, Some(4));
+    }
+
+    #[test]
+    fn parse_reads_extended_multiplexing() {
+        let network = Network::parse("\
+BO_ 200 Muxed: 8 ECU
+ SG_ Mux M : 0|8@1+ (1,0) [0|255] \"\" PC
+ SG_ Page m3 : 8|8@1+ (1,0) [0|255] \"\" PC
+
+SG_MUL_VAL_ 200 Page Mux 3-3, 5-7;
+");
+        let page = ObjectRef::Signal(200, "Page".to_string());
+        assert_eq!(network.multiplexing_of(&page), Multiplexing { is_multiplexor: false, multiplexor_value: Some(3) });
+        assert!(network.multiplexing_of(&ObjectRef::Signal(200, "Mux".to_string())).is_multiplexor);
+        assert_eq!(network.extended_multiplexing[&page], ExtendedMultiplexing {
+            multiplexor: "Mux".to_string(),
+            ranges: vec![(3, 3), (5, 7)],
+        });
+    }
+
+    #[test]
+    fn value_descriptions_keep_negative_raw_values() {
+        let network = Network::parse("\
+BO_ 100 Msg: 8 ECU
+ SG_ Mode : 0|8@1- (1,0) [-128|127] \"\" PC
+
+VAL_TABLE_ Modes 1 \"On\" 0 \"Off\" -1 \"Error\" ;
+VAL_ 100 Mode 1 \"On\" -1 \"Error\" 0 \"\" ;
+");
+        let expected = BTreeMap::from([(-1, "Error".to_string()), (0, "Off".to_string()), (1, "On".to_string())]);
+        assert_eq!(network.value_tables["Modes"], expected);
+        assert_eq!(
+            network.value_descriptions_of(&ObjectRef::Signal(100, "Mode".to_string())),
+            BTreeMap::from([(-1, "Error".to_string()), (0, String::new()), (1, "On".to_string())]),
+        );
+        assert!(network.value_descriptions_of(&ObjectRef::Signal(100, "Other".to_string())).is_empty());
+    }
+}
+
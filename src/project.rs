@@ -0,0 +1,294 @@
+//! TwinCAN project files.
+//!
+//! A project file, conventionally named `*.twincan`, is TOML holding a pair
+//! of DBC files, the options they are compared with, ignore rules and the
+//! review of their differences, so a recurring comparison can be reopened:
+//!
+//! ```toml
+//! dbc1 = "release_1.dbc"
+//! dbc2 = "release_2.dbc"
+//! match_mode = "id-then-name"
+//!
+//! [tolerances.factor]
+//! relative = 0.0001
+//!
+//! [text_matching]
+//! strategy = "exact"
+//! threshold = 0.85
+//!
+//! [[ignore]]
+//! message = "Diag_*"
+//! field = "Cycle Time"
+//!
+//! [[reviews]]
+//! entity = "signal"
+//! message = "EngineData"
+//! signal = "EngineSpeed"
+//! field = "factor"
+//! status = "accepted"
+//! note = "Agreed with the supplier"
+//!
+//! [[reviews]]
+//! entity = "message"
+//! message = "EngineData"
+//! field = { attribute = "GenMsgSendType" }
+//! status = "rejected"
+//! ```
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::compare::{CompareOptions, MatchMode};
+use crate::ignore::IgnoreRules;
+use crate::model::{ComparisonResult, EntityKind, Field};
+use crate::similarity::TextMatching;
+use crate::tolerance::Tolerances;
+
+/// A saved comparison: the file pair, its options and its review.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Project {
+    /// Path of the first DBC file. Relative paths are relative to the
+    /// project file.
+    pub dbc1: String,
+    /// Path of the second DBC file. Relative paths are relative to the
+    /// project file.
+    pub dbc2: String,
+    pub match_mode: MatchMode,
+    pub tolerances: Tolerances,
+    pub text_matching: TextMatching,
+    #[serde(flatten)]
+    pub rules: IgnoreRules,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reviews: Vec<Review>,
+}
+
+/// Top-level keys of a project file; `defaults` and `ignore` belong to the
+/// flattened [`IgnoreRules`].
+const PROJECT_KEYS: [&str; 8] =
+    ["dbc1", "dbc2", "match_mode", "tolerances", "text_matching", "defaults", "ignore", "reviews"];
+
+/// Parses the TOML of a project file. Unknown top-level keys, such as a
+/// misspelled `ignor`, are rejected rather than silently dropped.
+impl FromStr for Project {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let table: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+        if let Some(key) = table.keys().find(|key| !PROJECT_KEYS.contains(&key.as_str())) {
+            return Err(format!("Unknown key '{}', expected one of {}", key, PROJECT_KEYS.join(", ")));
+        }
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+}
+
+/// Review state of a difference.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReviewStatus {
+    /// Not reviewed yet.
+    #[default]
+    Open,
+    /// The difference is intended.
+    Accepted,
+    /// The difference needs to be fixed.
+    Rejected,
+}
+
+/// Review annotation of one difference.
+///
+/// Differences are identified by what they are about, which stays the same
+/// when the files are compared again: the entity, message, signal, object
+/// and field, plus the sender of a Transmitter difference. The mux page of
+/// a signal is not part of it, so a review survives a signal moving to
+/// another page.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Review {
+    pub entity: EntityKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal: Option<String>,
+    /// Name of an object that is not a message or signal, such as a node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object: Option<String>,
+    pub field: Field,
+    /// Sender of a Transmitter difference.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    #[serde(default)]
+    pub status: ReviewStatus,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+impl Review {
+    /// Returns `true` when the review annotates `result`.
+    pub fn is_for(&self, result: &ComparisonResult) -> bool {
+        self.entity == result.entity
+            && self.message == result.message
+            && self.signal == result.signal
+            && self.object == result.object
+            && self.field == result.field
+            && self.sender.as_deref() == result.sender()
+    }
+}
+
+impl Project {
+    /// Reads the project file at `path`, resolving the DBC paths against the
+    /// directory of the project file.
+    pub fn load(path: &str) -> Result<Project, Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+        let mut project: Project = text.parse().map_err(|e| format!("Invalid project file '{}': {}", path, e))?;
+
+        let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+        for dbc in [&mut project.dbc1, &mut project.dbc2] {
+            if !dbc.is_empty() && Path::new(dbc.as_str()).is_relative() {
+                *dbc = directory.join(dbc.as_str()).to_string_lossy().to_string();
+            }
+        }
+        Ok(project)
+    }
+
+    /// Writes the project file to `path`. DBC paths inside the directory of
+    /// the project file are written relative to it, so the project can be
+    /// moved along with its files.
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut project = self.clone();
+        let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+        for dbc in [&mut project.dbc1, &mut project.dbc2] {
+            if let Ok(relative) = Path::new(dbc.as_str()).strip_prefix(directory) {
+                *dbc = relative.to_string_lossy().to_string();
+            }
+        }
+        fs::write(path, toml::to_string_pretty(&project)?)?;
+        Ok(())
+    }
+
+    /// Options to compare the file pair with.
+    pub fn compare_options(&self) -> CompareOptions {
+        CompareOptions {
+            match_mode: self.match_mode,
+            tolerances: self.tolerances,
+            text_matching: self.text_matching,
+            ignore_rules: self.rules.rules(),
+        }
+    }
+
+    /// Review annotation of `result`, if it has one.
+    pub fn review_of(&self, result: &ComparisonResult) -> Option<&Review> {
+        self.reviews.iter().find(|review| review.is_for(result))
+    }
+
+    /// Sets the review annotation of `result`. An open review without a
+    /// note is removed.
+    pub fn set_review(&mut self, result: &ComparisonResult, status: ReviewStatus, note: &str) {
+        self.reviews.retain(|review| !review.is_for(result));
+        if status != ReviewStatus::Open || !note.is_empty() {
+            self.reviews.push(Review {
+                entity: result.entity,
+                message: result.message.clone(),
+                signal: result.signal.clone(),
+                object: result.object.clone(),
+                field: result.field.clone(),
+                sender: result.sender().map(str::to_string),
+                status,
+                note: note.to_string(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Value;
+
+    fn transmitter(sender: &str) -> ComparisonResult {
+        ComparisonResult::new(
+            EntityKind::Message,
+            "EngineData",
+            None,
+            Field::Transmitter,
+            None,
+            Some(Value::Text(sender.to_string())),
+        )
+    }
+
+    #[test]
+    fn reviews_tell_senders_apart() {
+        let mut project = Project::default();
+        project.set_review(&transmitter("ECU1"), ReviewStatus::Accepted, "Planned");
+        project.set_review(&transmitter("ECU2"), ReviewStatus::Rejected, "Not agreed");
+
+        assert_eq!(project.review_of(&transmitter("ECU1")).unwrap().note, "Planned");
+        assert_eq!(project.review_of(&transmitter("ECU2")).unwrap().note, "Not agreed");
+        assert!(project.review_of(&transmitter("ECU3")).is_none());
+    }
+
+    #[test]
+    fn reviews_survive_a_mux_page_change() {
+        let factor = ComparisonResult::new(
+            EntityKind::Signal,
+            "Read_Object",
+            Some("Read_Actual_Power"),
+            Field::Factor,
+            Some(Value::Float(0.019073777)),
+            Some(Value::Float(0.02)),
+        );
+        let mut project = Project::default();
+        project.set_review(&factor.clone().with_mux_page(Some(509)), ReviewStatus::Accepted, "");
+
+        assert!(project.review_of(&factor.with_mux_page(Some(510))).is_some());
+    }
+
+    #[test]
+    fn reviews_round_trip_through_toml() {
+        let attribute = ComparisonResult::new(
+            EntityKind::Message,
+            "EngineData",
+            None,
+            Field::Attribute("GenMsgSendType".to_string()),
+            None,
+            Some(Value::Text("Cyclic".to_string())),
+        );
+        let mut project = Project::default();
+        project.set_review(&attribute, ReviewStatus::Rejected, "");
+        project.set_review(&transmitter("ECU1"), ReviewStatus::Accepted, "Planned");
+
+        let saved: Project = toml::from_str(&toml::to_string_pretty(&project).unwrap()).unwrap();
+        assert_eq!(saved, project);
+        assert_eq!(saved.review_of(&attribute).unwrap().status, ReviewStatus::Rejected);
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let error = "dbc1 = \"a.dbc\"\nignor = []\n".parse::<Project>().unwrap_err();
+        assert!(error.contains("'ignor'"), "{}", error);
+
+        assert!("[tolerances.factr]\nabsolute = 0.1\n".parse::<Project>().is_err());
+        assert!("[text_matching]\nstrategie = \"exact\"\n".parse::<Project>().is_err());
+        assert!("[[reviews]]\nentity = \"signal\"\nfield = \"factor\"\nstatuss = \"accepted\"\n"
+            .parse::<Project>()
+            .is_err());
+    }
+
+    #[test]
+    fn every_saved_key_is_known() {
+        let mut project = Project {
+            dbc1: "a.dbc".to_string(),
+            dbc2: "b.dbc".to_string(),
+            tolerances: "all=0.1".parse().unwrap(),
+            rules: IgnoreRules { defaults: false, ignore: crate::ignore::default_ignore_rules() },
+            ..Project::default()
+        };
+        project.set_review(&transmitter("ECU1"), ReviewStatus::Accepted, "");
+
+        let text = toml::to_string_pretty(&project).unwrap();
+        assert_eq!(text.parse::<Project>().unwrap(), project);
+    }
+}
+
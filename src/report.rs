@@ -0,0 +1,360 @@
+//! Rendering of typed comparison results into display strings.
+//!
+//! The GUI table, the command line output and the CSV export all render
+//! through [`render`], so every view shows the same text. Three-way results
+//! render through [`render_three_way`], which builds on it.
+
+use std::fmt;
+use crate::model::{
+    ChangeKind, ComparisonResult, Detail, EntityKind, Field, IdFormat, MultiplexerType, RangeFit, Value, ValueType,
+};
+use crate::network::{AccessType, AttributeObject, ExtendedValueType, VariableType};
+use crate::severity::{classify, Severity};
+use crate::three_way::{ThreeWayChange, ThreeWayResult};
+use crate::tolerance::Tolerance;
+
+/// A comparison result rendered into the columns of the results table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Row {
+    pub result_type: String,
+    pub severity: String,
+    /// The message name, or the object name for objects outside messages.
+    pub message: String,
+    pub signal: String,
+    pub field: String,
+    pub dbc1: String,
+    pub dbc2: String,
+    pub details: String,
+}
+
+/// Headers of the two value columns for a set of results.
+///
+/// Comparisons show the values in each file; lint results show the value
+/// found and the reference it conflicts with.
+pub fn value_headers(results: &[ComparisonResult]) -> (&'static str, &'static str) {
+    if !results.is_empty() && results.iter().all(|result| result.change == ChangeKind::Problem) {
+        ("Value", "Reference")
+    } else {
+        ("DBC1", "DBC2")
+    }
+}
+
+/// Renders a comparison result into table columns.
+pub fn render(result: &ComparisonResult) -> Row {
+    let (dbc1, dbc2) = match &result.field {
+        Field::Exists => match result.change {
+            ChangeKind::Added => ("No".to_string(), "Yes".to_string()),
+            _ => ("Yes".to_string(), "No".to_string()),
+        },
+        field => (render_value(field, result.dbc1.as_ref()), render_value(field, result.dbc2.as_ref())),
+    };
+
+    Row {
+        result_type: result.entity.to_string(),
+        severity: classify(result).to_string(),
+        message: result.message.clone().or_else(|| result.object.clone()).unwrap_or_default(),
+        signal: match (&result.signal, result.mux_page) {
+            (Some(signal), Some(page)) => format!("{} [m{}]", signal, page),
+            (signal, _) => signal.clone().unwrap_or_default(),
+        },
+        field: result.field.to_string(),
+        dbc1,
+        dbc2,
+        details: result.details.iter().map(Detail::to_string).collect::<Vec<_>>().join("; "),
+    }
+}
+
+/// A three-way result rendered into table columns, with the value in the
+/// common ancestor and in each version.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThreeWayRow {
+    pub change: String,
+    pub result_type: String,
+    /// Severity of the riskier of the two changes.
+    pub severity: String,
+    pub message: String,
+    pub signal: String,
+    pub field: String,
+    pub base: String,
+    pub a: String,
+    pub b: String,
+    pub details: String,
+}
+
+/// Renders a three-way result into table columns. A version that left the
+/// property as it was shows the ancestor's value.
+pub fn render_three_way(result: &ThreeWayResult) -> ThreeWayRow {
+    let row = render(result.result());
+    let row_a = result.a.as_ref().map(render);
+    let row_b = result.b.as_ref().map(render);
+    let severity = result.a.iter().chain(&result.b).map(classify).max().unwrap_or(Severity::Cosmetic);
+
+    let mut details: Vec<String> = row_a.iter().chain(&row_b).map(|row| row.details.clone()).collect();
+    details.retain(|detail| !detail.is_empty());
+    details.dedup();
+
+    ThreeWayRow {
+        change: result.change.to_string(),
+        result_type: row.result_type,
+        severity: severity.to_string(),
+        message: row.message,
+        signal: row.signal,
+        field: row.field,
+        a: row_a.map_or_else(|| row.dbc1.clone(), |row| row.dbc2),
+        b: row_b.map_or_else(|| row.dbc1.clone(), |row| row.dbc2),
+        base: row.dbc1,
+        details: details.join("; "),
+    }
+}
+
+fn render_value(field: &Field, value: Option<&Value>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => match field {
+            Field::Unit => "No Unit".to_string(),
+            Field::Transmitter => "No Transmitter".to_string(),
+            Field::Receivers => "No Receivers".to_string(),
+            Field::AccessNodes => "No Nodes".to_string(),
+            Field::UndeclaredReceiver => "Not in BU_".to_string(),
+            Field::DataSize => "Not Data".to_string(),
+            Field::SignalGroup(_) => "No Group".to_string(),
+            Field::MultiplexorValue | Field::MultiplexorSignal => "Not Multiplexed".to_string(),
+            Field::MultiplexorRanges => "None".to_string(),
+            Field::PhysicalRange => "Floating Point".to_string(),
+            Field::ValueDescription(_) => "No Description".to_string(),
+            Field::Comment => "No Comment".to_string(),
+            Field::Attribute(_) | Field::AttributeDefault => "Not Set".to_string(),
+            Field::AttributeRange | Field::EnumValues | Field::ReferencedBy => "None".to_string(),
+            _ => String::new(),
+        },
+    }
+}
+
+impl fmt::Display for EntityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntityKind::Network => write!(f, "Network"),
+            EntityKind::Node => write!(f, "Node"),
+            EntityKind::Attribute => write!(f, "Attribute"),
+            EntityKind::ValueTable => write!(f, "Value Table"),
+            EntityKind::EnvironmentVariable => write!(f, "Environment Variable"),
+            EntityKind::Message => write!(f, "Message"),
+            EntityKind::Signal => write!(f, "Signal"),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Cosmetic => write!(f, "Cosmetic"),
+            Severity::PotentiallyBreaking => write!(f, "Potentially Breaking"),
+            Severity::Breaking => write!(f, "Breaking"),
+        }
+    }
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "Added"),
+            ChangeKind::Removed => write!(f, "Removed"),
+            ChangeKind::Modified => write!(f, "Modified"),
+            ChangeKind::Renamed => write!(f, "Renamed"),
+            ChangeKind::Problem => write!(f, "Problem"),
+        }
+    }
+}
+
+impl fmt::Display for ThreeWayChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThreeWayChange::OnlyInA => write!(f, "Changed in A"),
+            ThreeWayChange::OnlyInB => write!(f, "Changed in B"),
+            ThreeWayChange::Identical => write!(f, "Changed Identically"),
+            ThreeWayChange::Conflict => write!(f, "Conflict"),
+        }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Field::Exists => write!(f, "Exists"),
+            Field::Name => write!(f, "Renamed"),
+            Field::Dlc => write!(f, "DLC"),
+            Field::CycleTime => write!(f, "Cycle Time"),
+            Field::Transmitter => write!(f, "Transmitter"),
+            Field::MessageId => write!(f, "Message ID"),
+            Field::IdFormat => write!(f, "ID Format"),
+            Field::Layout => write!(f, "Bit Layout"),
+            Field::Length => write!(f, "Length"),
+            Field::Factor => write!(f, "Factor"),
+            Field::Offset => write!(f, "Offset"),
+            Field::MinValue => write!(f, "Min Value"),
+            Field::MaxValue => write!(f, "Max Value"),
+            Field::PhysicalRange => write!(f, "Physical Range"),
+            Field::RangeFit => write!(f, "Declared Range"),
+            Field::Unit => write!(f, "Unit"),
+            Field::ValueType => write!(f, "Value Type"),
+            Field::ExtendedValueType => write!(f, "Extended Value Type"),
+            Field::Receivers => write!(f, "Receivers"),
+            Field::MultiplexerType => write!(f, "Multiplexer Type"),
+            Field::MultiplexorValue => write!(f, "Multiplexor Value"),
+            Field::MultiplexorSignal => write!(f, "Multiplexor Signal"),
+            Field::MultiplexorRanges => write!(f, "Multiplexor Ranges"),
+            Field::InitialValueVector => write!(f, "Initial Value (Vector)"),
+            Field::InitialValueRaw => write!(f, "Initial Value (Raw)"),
+            Field::ValueDescription(value) if *value < 0 => write!(f, "Value -0x{:X} Description", value.unsigned_abs()),
+            Field::ValueDescription(value) => write!(f, "Value 0x{:X} Description", value),
+            Field::Comment => write!(f, "Comment"),
+            Field::Attribute(name) => write!(f, "Attribute {}", name),
+            Field::AttributeObject => write!(f, "Object Type"),
+            Field::AttributeType => write!(f, "Value Type"),
+            Field::AttributeRange => write!(f, "Range"),
+            Field::EnumValues => write!(f, "Enum Values"),
+            Field::AttributeDefault => write!(f, "Default"),
+            Field::ReferencedBy => write!(f, "Referenced By"),
+            Field::SignalGroup(name) => write!(f, "Signal Group {}", name),
+            Field::VariableType => write!(f, "Variable Type"),
+            Field::InitialValue => write!(f, "Initial Value"),
+            Field::AccessType => write!(f, "Access Type"),
+            Field::AccessNodes => write!(f, "Access Nodes"),
+            Field::DataSize => write!(f, "Data Size"),
+            Field::Overlap => write!(f, "Overlaps Signal"),
+            Field::BeyondPayload => write!(f, "Beyond Payload"),
+            Field::DuplicateId => write!(f, "Duplicate ID"),
+            Field::UndeclaredReceiver => write!(f, "Undeclared Receiver"),
+        }
+    }
+}
+
+impl fmt::Display for Detail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Detail::Confidence(confidence) => write!(f, "Confidence {:.0}%", confidence * 100.0),
+            Detail::ReferencedBy(signals) => write!(f, "Used by {}", signals.join(", ")),
+            Detail::Tolerance(tolerance) => write!(f, "Tolerance {}", tolerance),
+            Detail::Similarity(similarity) => write!(f, "Similarity {:.0}%", similarity * 100.0),
+        }
+    }
+}
+
+impl fmt::Display for Tolerance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.absolute, self.relative) {
+            (absolute, 0.0) => write!(f, "±{}", absolute),
+            (f64::EPSILON, relative) => write!(f, "±{}%", relative * 100.0),
+            (absolute, relative) => write!(f, "±{} or ±{}%", absolute, relative * 100.0),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Integer(value) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{}", value),
+            Value::Id(id) => write!(f, "0x{:X}", id),
+            Value::Text(text) => write!(f, "{}", text),
+            Value::List(items) => write!(f, "{}", items.join(",")),
+            Value::Range(min, max) => write!(f, "[{}|{}]", min, max),
+            Value::ValueType(value_type) => write!(f, "{}", value_type),
+            Value::MultiplexerType(multiplexer_type) => write!(f, "{}", multiplexer_type),
+            Value::IdFormat(id_format) => write!(f, "{}", id_format),
+            Value::AttributeObject(object) => write!(f, "{}", object),
+            Value::ExtendedValueType(value_type) => write!(f, "{}", value_type),
+            Value::VariableType(variable_type) => write!(f, "{}", variable_type),
+            Value::AccessType(access_type) => write!(f, "{}", access_type),
+            Value::RangeFit(range_fit) => write!(f, "{}", range_fit),
+            Value::IntegerRanges(ranges) => {
+                let ranges: Vec<String> = ranges
+                    .iter()
+                    .map(|(from, to)| if from == to { from.to_string() } else { format!("{}-{}", from, to) })
+                    .collect();
+                write!(f, "{}", ranges.join(","))
+            },
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueType::Unsigned => write!(f, "Unsigned"),
+            ValueType::Signed => write!(f, "Signed"),
+        }
+    }
+}
+
+impl fmt::Display for MultiplexerType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultiplexerType::Plain => write!(f, "Plain"),
+            MultiplexerType::Multiplexer => write!(f, "Multiplexer"),
+            MultiplexerType::Multiplexed => write!(f, "Multiplexed"),
+        }
+    }
+}
+
+impl fmt::Display for RangeFit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeFit::Unspecified => write!(f, "Not Specified"),
+            RangeFit::Fits => write!(f, "Fits Raw Range"),
+            RangeFit::Exceeds => write!(f, "Exceeds Raw Range"),
+        }
+    }
+}
+
+impl fmt::Display for IdFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdFormat::Standard => write!(f, "CAN Standard"),
+            IdFormat::Extended => write!(f, "CAN Extended"),
+        }
+    }
+}
+
+impl fmt::Display for AttributeObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeObject::Network => write!(f, "Network"),
+            AttributeObject::Node => write!(f, "Node"),
+            AttributeObject::Message => write!(f, "Message"),
+            AttributeObject::Signal => write!(f, "Signal"),
+            AttributeObject::EnvironmentVariable => write!(f, "Environment Variable"),
+        }
+    }
+}
+
+impl fmt::Display for ExtendedValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtendedValueType::Integer => write!(f, "Integer"),
+            ExtendedValueType::Float => write!(f, "IEEE Float"),
+            ExtendedValueType::Double => write!(f, "IEEE Double"),
+        }
+    }
+}
+
+impl fmt::Display for VariableType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VariableType::Integer => write!(f, "Integer"),
+            VariableType::Float => write!(f, "Float"),
+            VariableType::String => write!(f, "String"),
+        }
+    }
+}
+
+impl fmt::Display for AccessType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessType::Unrestricted => write!(f, "Unrestricted"),
+            AccessType::Read => write!(f, "Read"),
+            AccessType::Write => write!(f, "Write"),
+            AccessType::ReadWrite => write!(f, "Read/Write"),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Compatibility classification of comparison results.
+
+use std::str::FromStr;
+use crate::model::{ChangeKind, ComparisonResult, EntityKind, Field, RangeFit, Value};
+
+/// How much risk a difference carries for nodes and tools using the DBC.
+///
+/// Severities are ordered from least to most risky, so results can be
+/// sorted or filtered with `>=`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Documentation only, such as unit text, comments and description wording.
+    Cosmetic,
+    /// Changes the meaning of values without moving them, such as scaling
+    /// and value type, or affects code generated from the DBC.
+    PotentiallyBreaking,
+    /// Changes what is on the bus: bit layout, IDs or a shorter DLC.
+    Breaking,
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(severity: &str) -> Result<Self, Self::Err> {
+        match severity {
+            "cosmetic" => Ok(Severity::Cosmetic),
+            "potentially-breaking" => Ok(Severity::PotentiallyBreaking),
+            "breaking" => Ok(Severity::Breaking),
+            _ => Err(format!(
+                "Unknown severity '{}', expected cosmetic, potentially-breaking or breaking",
+                severity
+            )),
+        }
+    }
+}
+
+/// Classifies a comparison result by its compatibility risk.
+pub fn classify(result: &ComparisonResult) -> Severity {
+    match &result.field {
+        Field::Exists => match (result.entity, result.change) {
+            (EntityKind::ValueTable, _) => Severity::Cosmetic,
+            (EntityKind::Message | EntityKind::Signal | EntityKind::EnvironmentVariable, ChangeKind::Removed) => {
+                Severity::Breaking
+            },
+            _ => Severity::PotentiallyBreaking,
+        },
+
+        // Shrinking a message cuts off signals, growing it only adds room
+        Field::Dlc => match (&result.dbc1, &result.dbc2) {
+            (Some(Value::Integer(old)), Some(Value::Integer(new))) if new < old => Severity::Breaking,
+            _ => Severity::PotentiallyBreaking,
+        },
+
+        Field::MessageId
+        | Field::IdFormat
+        | Field::Layout
+        | Field::Length
+        | Field::ExtendedValueType
+        | Field::MultiplexerType
+        | Field::MultiplexorValue
+        | Field::MultiplexorSignal
+        | Field::MultiplexorRanges
+        | Field::VariableType
+        | Field::DataSize
+        | Field::Overlap
+        | Field::BeyondPayload
+        | Field::DuplicateId => Severity::Breaking,
+
+        Field::Name
+        | Field::CycleTime
+        | Field::Transmitter
+        | Field::Factor
+        | Field::Offset
+        | Field::MinValue
+        | Field::MaxValue
+        | Field::PhysicalRange
+        | Field::ValueType
+        | Field::Receivers
+        | Field::InitialValueVector
+        | Field::InitialValueRaw
+        | Field::InitialValue
+        | Field::SignalGroup(_)
+        | Field::Attribute(_)
+        | Field::AttributeObject
+        | Field::AttributeType
+        | Field::AttributeRange
+        | Field::EnumValues
+        | Field::AttributeDefault
+        | Field::AccessType
+        | Field::AccessNodes
+        | Field::UndeclaredReceiver => Severity::PotentiallyBreaking,
+
+        // A declared range that does not fit the raw bits needs a look
+        Field::RangeFit => match (result.change, &result.dbc2) {
+            (ChangeKind::Problem, _) | (_, Some(Value::RangeFit(RangeFit::Exceeds))) => Severity::PotentiallyBreaking,
+            _ => Severity::Cosmetic,
+        },
+
+        // Added or removed raw values change generated enums, reworded ones do not
+        Field::ValueDescription(_) => match result.change {
+            ChangeKind::Modified => Severity::Cosmetic,
+            _ => Severity::PotentiallyBreaking,
+        },
+
+        Field::Unit | Field::Comment | Field::ReferencedBy => Severity::Cosmetic,
+    }
+}
+
+/// Results at or above `min_severity`, optionally with the most severe first.
+pub fn filter_by_severity(
+    results: &[ComparisonResult],
+    min_severity: Severity,
+    sort_by_severity: bool,
+) -> Vec<ComparisonResult> {
+    let mut shown: Vec<ComparisonResult> =
+        results.iter().filter(|result| classify(result) >= min_severity).cloned().collect();
+    if sort_by_severity {
+        // Stable sort keeps the message and signal order within each severity
+        shown.sort_by_key(|result| std::cmp::Reverse(classify(result)));
+    }
+    shown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal_result(field: Field, dbc1: Option<Value>, dbc2: Option<Value>) -> ComparisonResult {
+        ComparisonResult::new(EntityKind::Signal, "Msg", Some("Sig"), field, dbc1, dbc2)
+    }
+
+    fn text(text: &str) -> Option<Value> {
+        Some(Value::Text(text.to_string()))
+    }
+
+    #[test]
+    fn shrinking_a_message_breaks_and_growing_it_does_not() {
+        let dlc = |old, new| {
+            let (old, new) = (Some(Value::Integer(old)), Some(Value::Integer(new)));
+            ComparisonResult::new(EntityKind::Message, "Msg", None, Field::Dlc, old, new)
+        };
+        assert_eq!(classify(&dlc(8, 6)), Severity::Breaking);
+        assert_eq!(classify(&dlc(6, 8)), Severity::PotentiallyBreaking);
+    }
+
+    #[test]
+    fn removing_a_signal_breaks_and_adding_one_does_not() {
+        let removed = ComparisonResult::exists(EntityKind::Signal, "Msg", Some("Sig"), ChangeKind::Removed);
+        let added = ComparisonResult::exists(EntityKind::Signal, "Msg", Some("Sig"), ChangeKind::Added);
+        assert_eq!(classify(&removed), Severity::Breaking);
+        assert_eq!(classify(&added), Severity::PotentiallyBreaking);
+    }
+
+    #[test]
+    fn rewording_a_value_description_is_cosmetic() {
+        let reworded = signal_result(Field::ValueDescription(1), text("On"), text("Enabled"));
+        let added = signal_result(Field::ValueDescription(2), None, text("Error"));
+        assert_eq!(classify(&reworded), Severity::Cosmetic);
+        assert_eq!(classify(&added), Severity::PotentiallyBreaking);
+    }
+
+    #[test]
+    fn documentation_changes_are_cosmetic() {
+        assert_eq!(classify(&signal_result(Field::Comment, text("Speed"), text("Vehicle speed"))), Severity::Cosmetic);
+        assert_eq!(classify(&signal_result(Field::Unit, text("km/h"), text("kph"))), Severity::Cosmetic);
+    }
+
+    #[test]
+    fn layout_and_scaling_changes_are_classified() {
+        let layout = signal_result(
+            Field::Layout,
+            Some(Value::IntegerRanges(vec![(0, 7)])),
+            Some(Value::IntegerRanges(vec![(8, 15)])),
+        );
+        let factor = signal_result(Field::Factor, Some(Value::Float(1.0)), Some(Value::Float(0.5)));
+        assert_eq!(classify(&layout), Severity::Breaking);
+        assert_eq!(classify(&factor), Severity::PotentiallyBreaking);
+    }
+
+    #[test]
+    fn only_exceeding_declared_ranges_need_a_look() {
+        let fit = |fit1, fit2| signal_result(Field::RangeFit, Some(Value::RangeFit(fit1)), Some(Value::RangeFit(fit2)));
+        assert_eq!(classify(&fit(RangeFit::Unspecified, RangeFit::Fits)), Severity::Cosmetic);
+        assert_eq!(classify(&fit(RangeFit::Fits, RangeFit::Exceeds)), Severity::PotentiallyBreaking);
+    }
+
+    #[test]
+    fn filtering_keeps_results_at_or_above_the_minimum() {
+        let comment = signal_result(Field::Comment, text("a"), text("b"));
+        let factor = signal_result(Field::Factor, Some(Value::Float(1.0)), Some(Value::Float(2.0)));
+        let removed = ComparisonResult::exists(EntityKind::Signal, "Msg", Some("Sig"), ChangeKind::Removed);
+        let results = vec![comment.clone(), factor.clone(), removed.clone()];
+
+        assert_eq!(filter_by_severity(&results, Severity::Cosmetic, false), results);
+        assert_eq!(
+            filter_by_severity(&results, Severity::PotentiallyBreaking, false),
+            vec![factor.clone(), removed.clone()],
+        );
+        assert_eq!(filter_by_severity(&results, Severity::Breaking, false), vec![removed.clone()]);
+        assert_eq!(filter_by_severity(&results, Severity::Cosmetic, true), vec![removed, factor, comment]);
+    }
+
+    #[test]
+    fn severities_parse_from_their_command_line_names() {
+        assert_eq!("potentially-breaking".parse::<Severity>(), Ok(Severity::PotentiallyBreaking));
+        assert!("major".parse::<Severity>().is_err());
+    }
+}
+
@@ -0,0 +1,177 @@
+//! Fuzzy text similarity used for descriptions and names.
+
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// Normalizes free text for comparison: lowercase, `-`/`_` treated as spaces,
+/// punctuation trimmed from words and words sorted alphabetically.
+pub fn normalize(text: &str) -> String {
+
+    let replaced = text
+        .to_lowercase()
+        .replace(['-', '_'], " ");
+
+    let mut words: Vec<String> = replaced
+        .to_lowercase()
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_string())
+        .collect();
+    
+    words.sort(); // Alphabetical sorting
+    words.join(" ")
+}
+
+/// Returns `true` when both texts are identical after [`normalize`].
+pub fn is_equivalent(term1: &str, term2: &str) -> bool {
+    normalize(term1) == normalize(term2)
+}
+
+/// Returns a similarity score between 0.0 and 1.0 for two texts.
+///
+/// Equivalent texts score 1.0; otherwise the Jaro-Winkler similarity of the
+/// normalized texts is returned.
+pub fn calculate_similarity(s1: &str, s2: &str) -> f64 {
+    // First check if they're equivalent after normalization
+    if is_equivalent(s1, s2) {
+        return 1.0;
+    }
+    
+    // If not equivalent, use Jaro-Winkler for partial similarity
+    let normalized_s1 = normalize(s1);
+    let normalized_s2 = normalize(s2);
+    
+    strsim::jaro_winkler(&normalized_s1, &normalized_s2)
+}
+
+/// How free texts, such as value descriptions and comments, are matched
+/// between the two files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextMatch {
+    /// Texts must be identical.
+    Exact,
+    /// Texts must be identical apart from letter case.
+    CaseInsensitive,
+    /// Texts must be identical after [`normalize`].
+    Normalized,
+    /// Texts match when the Jaro-Winkler similarity of the normalized texts
+    /// reaches the threshold.
+    #[default]
+    JaroWinkler,
+    /// Texts match when the normalized Levenshtein similarity of the
+    /// normalized texts reaches the threshold.
+    Levenshtein,
+}
+
+impl FromStr for TextMatch {
+    type Err = String;
+
+    fn from_str(strategy: &str) -> Result<Self, Self::Err> {
+        match strategy {
+            "exact" => Ok(TextMatch::Exact),
+            "case-insensitive" => Ok(TextMatch::CaseInsensitive),
+            "normalized" => Ok(TextMatch::Normalized),
+            "jaro-winkler" => Ok(TextMatch::JaroWinkler),
+            "levenshtein" => Ok(TextMatch::Levenshtein),
+            _ => Err(format!(
+                "Unknown text match '{}', expected exact, case-insensitive, normalized, jaro-winkler or levenshtein",
+                strategy
+            )),
+        }
+    }
+}
+
+impl TextMatch {
+    /// Returns a similarity score between 0.0 and 1.0 for two texts.
+    ///
+    /// The exact, case-insensitive and normalized strategies only score 1.0
+    /// for matching texts and 0.0 otherwise.
+    pub fn similarity(self, s1: &str, s2: &str) -> f64 {
+        let matches = match self {
+            TextMatch::Exact => s1 == s2,
+            TextMatch::CaseInsensitive => s1.to_lowercase() == s2.to_lowercase(),
+            TextMatch::Normalized => is_equivalent(s1, s2),
+            TextMatch::JaroWinkler => return calculate_similarity(s1, s2),
+            TextMatch::Levenshtein if is_equivalent(s1, s2) => true,
+            TextMatch::Levenshtein => return strsim::normalized_levenshtein(&normalize(s1), &normalize(s2)),
+        };
+        if matches { 1.0 } else { 0.0 }
+    }
+
+    /// Returns `true` for the strategies that score partial similarity,
+    /// Jaro-Winkler and Levenshtein, which a threshold applies to.
+    pub fn is_fuzzy(self) -> bool {
+        matches!(self, TextMatch::JaroWinkler | TextMatch::Levenshtein)
+    }
+}
+
+/// Parses the minimum similarity for two texts to count as unchanged.
+pub fn parse_threshold(threshold: &str) -> Result<f64, String> {
+    match threshold.trim().parse::<f64>() {
+        Ok(threshold) if (0.0..=1.0).contains(&threshold) => Ok(threshold),
+        _ => Err(format!("Invalid text threshold '{}', expected a similarity between 0 and 1", threshold)),
+    }
+}
+
+/// Strategy and threshold for matching free texts.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TextMatching {
+    pub strategy: TextMatch,
+    /// Minimum similarity for two texts to count as unchanged, for the
+    /// [fuzzy](TextMatch::is_fuzzy) strategies.
+    pub threshold: f64,
+}
+
+impl Default for TextMatching {
+    fn default() -> Self {
+        TextMatching { strategy: TextMatch::default(), threshold: 0.85 }
+    }
+}
+
+impl TextMatching {
+    /// Returns `true` when two texts count as changed: for fuzzy strategies
+    /// when their similarity is below the threshold, for the others when
+    /// they do not match.
+    pub fn differs(&self, s1: &str, s2: &str) -> bool {
+        let similarity = self.strategy.similarity(s1, s2);
+        if self.strategy.is_fuzzy() {
+            similarity < self.threshold
+        } else {
+            similarity < 1.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_matching_ignores_the_threshold() {
+        let matching = TextMatching { strategy: TextMatch::Exact, threshold: 0.0 };
+        assert!(matching.differs("on", "On"));
+        assert!(!matching.differs("on", "on"));
+    }
+
+    #[test]
+    fn binary_strategies_differ_when_texts_do_not_match() {
+        let case_insensitive = TextMatching { strategy: TextMatch::CaseInsensitive, threshold: 1.0 };
+        assert!(!case_insensitive.differs("Engine On", "engine on"));
+        assert!(case_insensitive.differs("Engine On", "Engine-On"));
+
+        let normalized = TextMatching { strategy: TextMatch::Normalized, threshold: 0.0 };
+        assert!(!normalized.differs("Engine-On", "on engine"));
+        assert!(normalized.differs("Engine On", "Engine Off"));
+    }
+
+    #[test]
+    fn fuzzy_strategies_differ_below_the_threshold() {
+        let strict = TextMatching { strategy: TextMatch::JaroWinkler, threshold: 1.0 };
+        let loose = TextMatching { strategy: TextMatch::JaroWinkler, threshold: 0.0 };
+        assert!(strict.differs("Engine running", "Engine runs"));
+        assert!(!loose.differs("Engine running", "Engine runs"));
+    }
+}
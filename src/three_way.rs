@@ -0,0 +1,318 @@
+//! Three-way comparison of two versions of a DBC file against their common
+//! ancestor.
+
+use std::error::Error;
+use std::collections::HashMap;
+use crate::compare::{compare_dbc_files_with, CompareOptions};
+use crate::model::{ChangeKind, ComparisonResult, EntityKind, Field, Value};
+use crate::network::DbcFile;
+use crate::tolerance::Tolerances;
+
+/// Which versions changed a property of the common ancestor, and whether
+/// their changes agree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ThreeWayChange {
+    /// Only version A changed the property.
+    OnlyInA,
+    /// Only version B changed the property.
+    OnlyInB,
+    /// Both versions made the same change.
+    Identical,
+    /// Both versions changed the property differently, or one changed an
+    /// object the other removed.
+    Conflict,
+}
+
+/// A change to the common ancestor made in one or both versions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThreeWayResult {
+    pub change: ThreeWayChange,
+    /// Difference from the ancestor to version A, `None` when A left the
+    /// property as it was.
+    pub a: Option<ComparisonResult>,
+    /// Difference from the ancestor to version B, `None` when B left the
+    /// property as it was.
+    pub b: Option<ComparisonResult>,
+}
+
+impl ThreeWayResult {
+    /// The difference as made in version A, or in version B when A did not
+    /// make it.
+    pub fn result(&self) -> &ComparisonResult {
+        match (&self.a, &self.b) {
+            (Some(result), _) | (None, Some(result)) => result,
+            (None, None) => unreachable!("a three-way result has at least one side"),
+        }
+    }
+}
+
+/// Compares versions `a` and `b` of a DBC file against their common
+/// ancestor `base`.
+///
+/// Both versions are compared with the ancestor as by
+/// [`compare_dbc_files_with`], so every field is compared, and ignored or
+/// within tolerance, the same way as in a two-way comparison. Differences
+/// are paired by type, message, signal, object and field, using the names
+/// in the ancestor, and classified as a [`ThreeWayChange`]. Objects both
+/// versions added are the same change when versions A and B agree on them.
+/// Results are sorted by message name, then signal name, after the
+/// differences outside messages.
+pub fn compare_three_way(
+    base: &DbcFile,
+    a: &DbcFile,
+    b: &DbcFile,
+    options: &CompareOptions,
+) -> Result<Vec<ThreeWayResult>, Box<dyn Error>> {
+    let changes_a = compare_dbc_files_with(base, a, options)?;
+    let changes_b = compare_dbc_files_with(base, b, options)?;
+    // Only consulted for objects both versions added
+    let a_to_b = compare_dbc_files_with(a, b, options)?;
+
+    let mut unpaired_b: HashMap<ChangeKey, ComparisonResult> =
+        changes_b.iter().map(|result| (change_key(result), result.clone())).collect();
+
+    let mut results = Vec::new();
+    for change_a in changes_a {
+        match unpaired_b.remove(&change_key(&change_a)) {
+            Some(change_b) => {
+                let change = if same_change(&change_a, &change_b, &a_to_b, &options.tolerances) {
+                    ThreeWayChange::Identical
+                } else {
+                    ThreeWayChange::Conflict
+                };
+                results.push(ThreeWayResult { change, a: Some(change_a), b: Some(change_b) });
+            },
+            None => results.push(ThreeWayResult { change: ThreeWayChange::OnlyInA, a: Some(change_a), b: None }),
+        }
+    }
+    // Keep the order of the differences only B made
+    for change_b in changes_b {
+        if let Some(change_b) = unpaired_b.remove(&change_key(&change_b)) {
+            results.push(ThreeWayResult { change: ThreeWayChange::OnlyInB, a: None, b: Some(change_b) });
+        }
+    }
+
+    mark_removal_conflicts(&mut results);
+
+    // Stable sort keeps the field order within each message and signal
+    results.sort_by(|r1, r2| {
+        let (result1, result2) = (r1.result(), r2.result());
+        (&result1.message, &result1.signal).cmp(&(&result2.message, &result2.signal))
+    });
+    Ok(results)
+}
+
+/// What a difference is about: its type, message, signal, object and field.
+/// Transmitters are reported one sender at a time, so the sender is part of
+/// the key.
+type ChangeKey = (EntityKind, Option<String>, Option<String>, Option<String>, Field, Option<String>);
+
+fn change_key(result: &ComparisonResult) -> ChangeKey {
+    (
+        result.entity,
+        result.message.clone(),
+        result.signal.clone(),
+        result.object.clone(),
+        result.field.clone(),
+        result.sender().map(str::to_string),
+    )
+}
+
+/// Returns `true` when both versions changed the ancestor the same way.
+fn same_change(
+    change_a: &ComparisonResult,
+    change_b: &ComparisonResult,
+    a_to_b: &[ComparisonResult],
+    tolerances: &Tolerances,
+) -> bool {
+    if change_a.change != change_b.change {
+        return false;
+    }
+
+    // Both added the object; it is the same when A and B do not differ on it
+    if change_a.field == Field::Exists && change_a.change == ChangeKind::Added {
+        return !a_to_b.iter().any(|result| {
+            result.message == change_a.message
+                && result.object == change_a.object
+                && (change_a.signal.is_none() || result.signal == change_a.signal)
+        });
+    }
+
+    // Compare the new values, floats within the tolerance of the field
+    match (tolerances.for_field(&change_a.field), &change_a.dbc2, &change_b.dbc2) {
+        (Some(tolerance), Some(Value::Float(value_a)), Some(Value::Float(value_b))) => {
+            !tolerance.differs(*value_a, *value_b)
+        },
+        _ => change_a.dbc2 == change_b.dbc2,
+    }
+}
+
+/// Marks changes to a message, signal or object that the other version
+/// removed, and the removal itself, as conflicts.
+fn mark_removal_conflicts(results: &mut [ThreeWayResult]) {
+    let mut conflicts = vec![false; results.len()];
+    for (i, removal) in results.iter().enumerate() {
+        for (j, change) in results.iter().enumerate() {
+            let conflicting = match (removal.change, change.change) {
+                (ThreeWayChange::OnlyInB, ThreeWayChange::OnlyInA) => removes(&removal.b, &change.a),
+                (ThreeWayChange::OnlyInA, ThreeWayChange::OnlyInB) => removes(&removal.a, &change.b),
+                _ => false,
+            };
+            if conflicting {
+                conflicts[i] = true;
+                conflicts[j] = true;
+            }
+        }
+    }
+
+    for (result, conflict) in results.iter_mut().zip(conflicts) {
+        if conflict {
+            result.change = ThreeWayChange::Conflict;
+        }
+    }
+}
+
+/// Returns `true` when `removal` removes the object `change` changed.
+/// Removals within a removed object do not conflict with it.
+fn removes(removal: &Option<ComparisonResult>, change: &Option<ComparisonResult>) -> bool {
+    let (Some(removal), Some(change)) = (removal, change) else {
+        return false;
+    };
+    let is_removal = |result: &ComparisonResult| result.field == Field::Exists && result.change == ChangeKind::Removed;
+
+    is_removal(removal)
+        && !is_removal(change)
+        && change.message == removal.message
+        && change.object == removal.object
+        && (removal.signal.is_none() || change.signal == removal.signal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_dbc;
+
+    const BASE: &str = "\
+VERSION \"\"
+
+BU_: ECU PC
+
+BO_ 100 Status: 8 ECU
+ SG_ Voltage : 7|16@0+ (0.001,0) [0|40.8] \"V\" PC
+ SG_ Coil : 16|1@1+ (1,0) [0|1] \"\" PC
+ SG_ Mode : 26|2@0+ (1,0) [0|3] \"\" PC
+";
+
+    /// A DBC file parsed from `text` with each `(from, to)` replacement made.
+    fn edited(text: &str, edits: &[(&str, &str)]) -> DbcFile {
+        let mut text = text.to_string();
+        for (from, to) in edits {
+            assert!(text.contains(from), "{}", from);
+            text = text.replace(from, to);
+        }
+        parse_dbc(text.as_bytes()).unwrap()
+    }
+
+    /// `BASE` with a new maximum voltage, a moved coil bit and a new mode factor.
+    fn changed() -> DbcFile {
+        edited(BASE, &[
+            ("[0|40.8]", "[0|50]"),
+            ("Coil : 16|1@1+", "Coil : 17|1@1+"),
+            ("Mode : 26|2@0+ (1,0)", "Mode : 26|2@0+ (2,0)"),
+        ])
+    }
+
+    fn compare(base: &DbcFile, a: &DbcFile, b: &DbcFile) -> Vec<ThreeWayResult> {
+        compare_three_way(base, a, b, &CompareOptions::default()).unwrap()
+    }
+
+    /// Changes of `field` of `signal`, in result order.
+    fn changes_of(results: &[ThreeWayResult], signal: &str, field: Field) -> Vec<ThreeWayChange> {
+        results
+            .iter()
+            .filter(|result| result.result().signal.as_deref() == Some(signal) && result.result().field == field)
+            .map(|result| result.change)
+            .collect()
+    }
+
+    #[test]
+    fn changes_made_in_one_version_belong_to_it() {
+        let base = edited(BASE, &[]);
+        let changed = changed();
+
+        let only_a = compare(&base, &changed, &base);
+        // The new factor also changes the mode's physical range
+        assert_eq!(only_a.len(), 4);
+        assert!(only_a.iter().all(|result| result.change == ThreeWayChange::OnlyInA && result.b.is_none()));
+
+        let only_b = compare(&base, &base, &changed);
+        assert_eq!(only_b.len(), only_a.len());
+        assert!(only_b.iter().all(|result| result.change == ThreeWayChange::OnlyInB && result.a.is_none()));
+    }
+
+    #[test]
+    fn changes_made_in_both_versions_are_identical() {
+        let base = edited(BASE, &[]);
+        let changed = changed();
+
+        let results = compare(&base, &changed, &changed);
+        assert_eq!(results.len(), 4);
+        assert!(results.iter().all(|result| result.change == ThreeWayChange::Identical));
+    }
+
+    #[test]
+    fn different_changes_to_a_property_conflict() {
+        let base = edited(BASE, &[]);
+        let a = changed();
+        let b = edited(BASE, &[("Mode : 26|2@0+ (1,0)", "Mode : 26|2@0+ (3,0)")]);
+
+        let results = compare(&base, &a, &b);
+        assert_eq!(changes_of(&results, "Mode", Field::Factor), vec![ThreeWayChange::Conflict]);
+        assert_eq!(changes_of(&results, "Coil", Field::Layout), vec![ThreeWayChange::OnlyInA]);
+    }
+
+    #[test]
+    fn changing_a_signal_the_other_version_removed_conflicts() {
+        let base = edited(BASE, &[]);
+        let a = changed();
+        let b = edited(BASE, &[(" SG_ Voltage : 7|16@0+ (0.001,0) [0|40.8] \"V\" PC\n", "")]);
+
+        let results = compare(&base, &a, &b);
+        assert_eq!(changes_of(&results, "Voltage", Field::MaxValue), vec![ThreeWayChange::Conflict]);
+        assert_eq!(changes_of(&results, "Voltage", Field::Exists), vec![ThreeWayChange::Conflict]);
+        assert_eq!(changes_of(&results, "Mode", Field::Factor), vec![ThreeWayChange::OnlyInA]);
+    }
+
+    const ADDED_SIGNAL: &str = " SG_ Added : 40|8@1+ (1,0) [0|255] \"\" PC\n";
+
+    /// `BASE` with `signal` added to the `Status` message.
+    fn with_added(signal: &str) -> DbcFile {
+        edited(BASE, &[("BO_ 100 Status: 8 ECU\n", &format!("BO_ 100 Status: 8 ECU\n{}", signal))])
+    }
+
+    #[test]
+    fn signals_both_versions_added_are_identical_only_when_equal() {
+        let base = edited(BASE, &[]);
+
+        let same = compare(&base, &with_added(ADDED_SIGNAL), &with_added(ADDED_SIGNAL));
+        assert_eq!(changes_of(&same, "Added", Field::Exists), vec![ThreeWayChange::Identical]);
+
+        let scaled = ADDED_SIGNAL.replace("(1,0)", "(2,0)");
+        let different = compare(&base, &with_added(ADDED_SIGNAL), &with_added(&scaled));
+        assert_eq!(changes_of(&different, "Added", Field::Exists), vec![ThreeWayChange::Conflict]);
+    }
+
+    #[test]
+    fn float_changes_within_tolerance_are_identical() {
+        let base = edited(BASE, &[]);
+        let a = edited(BASE, &[("[0|40.8]", "[0|50]")]);
+        let b = edited(BASE, &[("[0|40.8]", "[0|49.999993785]")]);
+
+        let exact = compare(&base, &a, &b);
+        assert_eq!(changes_of(&exact, "Voltage", Field::MaxValue), vec![ThreeWayChange::Conflict]);
+
+        let options = CompareOptions { tolerances: "max=0.001%".parse().unwrap(), ..CompareOptions::default() };
+        let tolerant = compare_three_way(&base, &a, &b, &options).unwrap();
+        assert_eq!(changes_of(&tolerant, "Voltage", Field::MaxValue), vec![ThreeWayChange::Identical]);
+    }
+}
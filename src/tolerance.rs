@@ -0,0 +1,216 @@
+//! Tolerances for comparing floating-point fields.
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::model::Field;
+
+/// How far apart two floats may be and still count as equal.
+///
+/// Values are equal when they are within the absolute or the relative
+/// tolerance, so export rounding such as `0.1` versus `0.100000001` can be
+/// absorbed by either.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tolerance {
+    /// Largest difference that still counts as equal.
+    #[serde(skip_serializing_if = "is_precision")]
+    pub absolute: f64,
+    /// Largest difference that still counts as equal, as a fraction of the
+    /// larger of the two magnitudes.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub relative: f64,
+}
+
+fn is_precision(absolute: &f64) -> bool {
+    *absolute == f64::EPSILON
+}
+
+fn is_zero(relative: &f64) -> bool {
+    *relative == 0.0
+}
+
+impl Default for Tolerance {
+    /// Only differences below float precision count as equal.
+    fn default() -> Self {
+        Tolerance { absolute: f64::EPSILON, relative: 0.0 }
+    }
+}
+
+impl Tolerance {
+    /// Returns `true` when `value1` and `value2` are further apart than the
+    /// tolerance allows.
+    pub fn differs(&self, value1: f64, value2: f64) -> bool {
+        let difference = (value1 - value2).abs();
+        difference > self.absolute && difference > self.relative * value1.abs().max(value2.abs())
+    }
+}
+
+/// Tolerance for each floating-point field of signals and environment
+/// variables.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tolerances {
+    #[serde(skip_serializing_if = "is_default")]
+    pub factor: Tolerance,
+    #[serde(skip_serializing_if = "is_default")]
+    pub offset: Tolerance,
+    /// Minimum values, also used for the lower bound of physical ranges.
+    #[serde(skip_serializing_if = "is_default")]
+    pub min: Tolerance,
+    /// Maximum values, also used for the upper bound of physical ranges.
+    #[serde(skip_serializing_if = "is_default")]
+    pub max: Tolerance,
+    /// Initial values, raw and Vector.
+    #[serde(skip_serializing_if = "is_default")]
+    pub initial: Tolerance,
+}
+
+fn is_default(tolerance: &Tolerance) -> bool {
+    *tolerance == Tolerance::default()
+}
+
+impl Tolerances {
+    /// Tolerance `field` is compared with, or `None` for fields that are not
+    /// floats.
+    pub fn for_field(&self, field: &Field) -> Option<Tolerance> {
+        match field {
+            Field::Factor => Some(self.factor),
+            Field::Offset => Some(self.offset),
+            Field::MinValue => Some(self.min),
+            Field::MaxValue => Some(self.max),
+            Field::InitialValueVector | Field::InitialValueRaw | Field::InitialValue => Some(self.initial),
+            _ => None,
+        }
+    }
+}
+
+/// Parses comma-separated `field=value` settings, such as
+/// `all=1e-6,factor=0.01%`, on top of the default tolerances.
+///
+/// Fields are `factor`, `offset`, `min`, `max`, `initial` or `all`. A value
+/// ending in `%` sets the relative tolerance, any other value the absolute
+/// one; later settings override earlier ones.
+impl FromStr for Tolerances {
+    type Err = String;
+
+    fn from_str(settings: &str) -> Result<Self, Self::Err> {
+        let mut tolerances = Tolerances::default();
+
+        for setting in settings.split(',').map(str::trim).filter(|setting| !setting.is_empty()) {
+            let Some((field, value)) = setting.split_once('=') else {
+                return Err(format!("Invalid tolerance '{}', expected field=value", setting));
+            };
+            let targets = match field.trim() {
+                "factor" => vec![&mut tolerances.factor],
+                "offset" => vec![&mut tolerances.offset],
+                "min" => vec![&mut tolerances.min],
+                "max" => vec![&mut tolerances.max],
+                "initial" => vec![&mut tolerances.initial],
+                "all" => vec![
+                    &mut tolerances.factor,
+                    &mut tolerances.offset,
+                    &mut tolerances.min,
+                    &mut tolerances.max,
+                    &mut tolerances.initial,
+                ],
+                field => {
+                    return Err(format!(
+                        "Unknown tolerance field '{}', expected factor, offset, min, max, initial or all",
+                        field
+                    ))
+                },
+            };
+
+            let value = value.trim();
+            let (number, relative) = match value.strip_suffix('%') {
+                Some(percent) => (percent.trim(), true),
+                None => (value, false),
+            };
+            let number = match number.parse::<f64>() {
+                Ok(number) if number >= 0.0 && number.is_finite() => number,
+                _ => return Err(format!("Invalid tolerance '{}', expected a non-negative number", value)),
+            };
+
+            for tolerance in targets {
+                if relative {
+                    tolerance.relative = number / 100.0;
+                } else {
+                    tolerance.absolute = number;
+                }
+            }
+        }
+
+        Ok(tolerances)
+    }
+}
+
+/// Formats the tolerances that differ from the default as the settings
+/// [`FromStr`] parses, such as `factor=0.000001, max=0.5%`.
+impl fmt::Display for Tolerances {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = [
+            ("factor", self.factor),
+            ("offset", self.offset),
+            ("min", self.min),
+            ("max", self.max),
+            ("initial", self.initial),
+        ];
+        let mut settings = Vec::new();
+        for (name, tolerance) in fields {
+            if tolerance.absolute != Tolerance::default().absolute {
+                settings.push(format!("{}={}", name, tolerance.absolute));
+            }
+            if tolerance.relative != 0.0 {
+                // Round away the float noise of converting to percent
+                let percent = (tolerance.relative * 100.0 * 1e12).round() / 1e12;
+                settings.push(format!("{}={}%", name, percent));
+            }
+        }
+        write!(f, "{}", settings.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_set_absolute_and_relative_tolerances() {
+        let tolerances: Tolerances = "all=1e-6, factor=0.01%, max=0.5".parse().unwrap();
+
+        assert_eq!(tolerances.factor, Tolerance { absolute: 1e-6, relative: 0.0001 });
+        assert_eq!(tolerances.offset, Tolerance { absolute: 1e-6, relative: 0.0 });
+        assert_eq!(tolerances.max, Tolerance { absolute: 0.5, relative: 0.0 });
+        assert_eq!(tolerances.initial.absolute, 1e-6);
+    }
+
+    #[test]
+    fn empty_settings_keep_the_defaults() {
+        assert_eq!("".parse::<Tolerances>().unwrap(), Tolerances::default());
+        assert_eq!(" , ".parse::<Tolerances>().unwrap(), Tolerances::default());
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        for settings in ["factor", "speed=1", "min=-1", "max=abc", "offset=inf", "all=%"] {
+            assert!(settings.parse::<Tolerances>().is_err(), "{}", settings);
+        }
+    }
+
+    #[test]
+    fn display_round_trips_through_from_str() {
+        let tolerances: Tolerances = "factor=1e-6,max=0.5%".parse().unwrap();
+        assert_eq!(tolerances.to_string(), "factor=0.000001, max=0.5%");
+        assert_eq!(tolerances.to_string().parse::<Tolerances>().unwrap(), tolerances);
+    }
+
+    #[test]
+    fn values_within_either_tolerance_are_equal() {
+        let tolerance = Tolerance { absolute: 0.01, relative: 0.001 };
+        assert!(!tolerance.differs(1.0, 1.005));
+        assert!(!tolerance.differs(1000.0, 1000.5));
+        assert!(tolerance.differs(1.0, 1.02));
+    }
+}
+